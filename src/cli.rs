@@ -1,4 +1,7 @@
-use clap::Parser;
+use crate::core::output::OutputFormat;
+use crate::core::samples::Sample;
+use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
 #[command(name = "ccline")]
@@ -31,6 +34,79 @@ pub struct Cli {
     /// Patch Claude Code cli.js to disable context warnings
     #[arg(long = "patch")]
     pub patch: Option<String>,
+
+    /// Output target; anything but ansi renders the current directory when stdin is empty
+    #[arg(long = "output-format", value_enum, default_value_t = OutputFormat::Ansi)]
+    pub output_format: OutputFormat,
+
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Commands {
+    /// Inspect model configuration (models.toml)
+    Models {
+        #[command(subcommand)]
+        action: ModelsCommand,
+    },
+    /// Manage segment plugins (ccline-segment-* executables)
+    Plugins {
+        #[command(subcommand)]
+        action: PluginsCommand,
+    },
+    /// Render the statusline from recorded or sample input, or export it as SVG/HTML
+    Render(RenderArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct RenderArgs {
+    /// Claude Code JSON input instead of stdin; one document per line is a recorded session
+    #[arg(long, conflicts_with_all = ["sample", "transcript"])]
+    pub input: Option<PathBuf>,
+
+    /// Built-in sample session
+    #[arg(long, value_enum, conflicts_with = "transcript")]
+    pub sample: Option<Sample>,
+
+    /// Replay a Claude Code transcript (.jsonl), one frame per response
+    #[arg(long)]
+    pub transcript: Option<PathBuf>,
+
+    /// Render every frame of a session instead of only the last one
+    #[arg(long)]
+    pub frames: bool,
+
+    /// Write the statusline as an SVG image
+    #[arg(long)]
+    pub svg: Option<PathBuf>,
+
+    /// Write the statusline as an HTML page
+    #[arg(long)]
+    pub html: Option<PathBuf>,
+
+    /// Fonts to try before the Nerd Font fallbacks, comma separated
+    #[arg(long, value_delimiter = ',')]
+    pub font: Vec<String>,
+
+    /// Font size in pixels for SVG/HTML export
+    #[arg(long, default_value_t = 14.0)]
+    pub font_size: f32,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ModelsCommand {
+    /// Show which models.toml entry matches a model ID
+    Test {
+        /// Model ID as reported by Claude Code (e.g. claude-sonnet-4-5-20250929)
+        model_id: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PluginsCommand {
+    /// List installed plugins and their handshake results
+    List,
 }
 
 impl Cli {
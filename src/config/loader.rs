@@ -1,4 +1,4 @@
-use super::types::Config;
+use super::types::{Config, SegmentId};
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -103,6 +103,17 @@ impl ConfigLoader {
 }
 
 impl Config {
+    /// This config switched to `theme`, keeping the settings that describe
+    /// this machine rather than the theme: color mode, glyph widths and
+    /// trusted projects
+    pub fn themed(&self, theme: &str) -> Config {
+        let mut config = crate::ui::themes::ThemePresets::get_theme(theme);
+        config.style.color_mode = self.style.color_mode;
+        config.style.width = self.style.width.clone();
+        config.trusted_projects = self.trusted_projects.clone();
+        config
+    }
+
     /// Load configuration from default location
     pub fn load() -> Result<Config, Box<dyn std::error::Error>> {
         // Ensure themes directory exists and has built-in themes
@@ -173,11 +184,53 @@ impl Config {
             return Err("No segments configured".into());
         }
 
-        // Validate segment IDs are unique
-        let mut seen_ids = std::collections::HashSet::new();
+        // Validate segment keys are unique; repeated segment types need distinct names
+        let mut seen_keys = std::collections::HashSet::new();
         for segment in &self.segments {
-            if !seen_ids.insert(segment.id) {
-                return Err(format!("Duplicate segment ID: {:?}", segment.id).into());
+            match segment.id {
+                SegmentId::Custom if !segment.options.contains_key("command") => {
+                    return Err("Custom segment is missing the \"command\" option".into());
+                }
+                SegmentId::Plugin => {
+                    let name = segment.options.get("plugin").and_then(|v| v.as_str());
+                    let Some(name) = name else {
+                        return Err("Plugin segment is missing the \"plugin\" option".into());
+                    };
+                    if !crate::core::segments::plugin::is_valid_plugin_name(name) {
+                        return Err(format!("Invalid plugin name: {}", name).into());
+                    }
+                }
+                SegmentId::Script => {
+                    crate::core::segments::ScriptSegment::validate(&segment.options)?;
+                }
+                _ => {}
+            }
+
+            for (field, rule) in [("show_when", &segment.show_when), ("hide_when", &segment.hide_when)] {
+                if let Some(rule) = rule {
+                    super::rules::Rule::parse(rule).map_err(|e| {
+                        format!("Invalid {} for segment {}: {}", field, segment.key(), e)
+                    })?;
+                }
+            }
+
+            if segment.name.as_deref().is_some_and(|name| name.trim().is_empty()) {
+                return Err(format!("Empty name for {:?} segment", segment.id).into());
+            }
+
+            // Unnamed command segments could repeat before names existed
+            let unnamed_command = segment.name.is_none()
+                && matches!(
+                    segment.id,
+                    SegmentId::Custom | SegmentId::Plugin | SegmentId::Script
+                );
+            let key = segment.key();
+            if !seen_keys.insert(key.clone()) && !unnamed_command {
+                return Err(format!(
+                    "Duplicate segment key: {} (give repeated segments a unique name)",
+                    key
+                )
+                .into());
             }
         }
 
@@ -191,3 +244,76 @@ impl Config {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SegmentConfig;
+    use serde_json::json;
+
+    fn segment(id: SegmentId, name: Option<&str>, option: Option<(&str, &str)>) -> SegmentConfig {
+        let mut segment = Config::default().segments[0].clone();
+        segment.id = id;
+        segment.name = name.map(str::to_string);
+        segment.options = option
+            .map(|(key, value)| (key.to_string(), json!(value)))
+            .into_iter()
+            .collect();
+        segment
+    }
+
+    #[test]
+    fn segment_keys_come_from_name_plugin_or_type() {
+        assert_eq!(segment(SegmentId::Git, None, None).key(), "git");
+        assert_eq!(
+            segment(SegmentId::OutputStyle, None, None).key(),
+            "output_style"
+        );
+        assert_eq!(segment(SegmentId::Git, Some("repo"), None).key(), "repo");
+        let plugin = segment(SegmentId::Plugin, None, Some(("plugin", "oncall")));
+        assert_eq!(plugin.key(), "oncall");
+    }
+
+    #[test]
+    fn check_requires_unique_keys_except_for_unnamed_command_segments() {
+        let check = |segments: Vec<SegmentConfig>| {
+            Config {
+                segments,
+                ..Config::default()
+            }
+            .check()
+        };
+        let custom = || segment(SegmentId::Custom, None, Some(("command", "date")));
+        let plugin = || segment(SegmentId::Plugin, None, Some(("plugin", "oncall")));
+
+        assert!(check(vec![custom(), custom(), plugin(), plugin()]).is_ok());
+        assert!(check(vec![
+            segment(SegmentId::Directory, None, None),
+            segment(SegmentId::Directory, Some("full-path"), None),
+        ])
+        .is_ok());
+
+        let error = check(vec![
+            segment(SegmentId::Git, None, None),
+            segment(SegmentId::Git, None, None),
+        ])
+        .unwrap_err();
+        assert!(error.to_string().contains("Duplicate segment key: git"));
+        assert!(check(vec![
+            segment(SegmentId::Git, Some("repo"), None),
+            segment(SegmentId::Directory, Some("repo"), None),
+        ])
+        .is_err());
+        assert!(check(vec![segment(SegmentId::Git, Some(" "), None)]).is_err());
+    }
+
+    #[test]
+    fn width_values_must_be_one_or_two_cells() {
+        let parse = |toml: &str| toml::from_str::<crate::config::WidthConfig>(toml);
+
+        let widths = parse("ambiguous = 2\nprivate_use = 1").unwrap();
+        assert_eq!((widths.ambiguous, widths.private_use), (2, 1));
+        assert!(parse("ambiguous = 3").is_err());
+        assert!(parse("private_use = 0").is_err());
+    }
+}
@@ -1,6 +1,8 @@
 pub mod defaults;
 pub mod loader;
 pub mod models;
+pub mod project;
+pub mod rules;
 pub mod types;
 
 pub use loader::ConfigLoader;
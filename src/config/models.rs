@@ -1,6 +1,7 @@
+use regex::{Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelConfig {
@@ -11,10 +12,98 @@ pub struct ModelConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelEntry {
     pub pattern: String,
+    /// How `pattern` is compared against the model ID (defaults to substring match)
+    #[serde(rename = "match", default)]
+    pub match_kind: MatchKind,
+    /// Higher priority entries win over lower ones; ties keep file order
+    #[serde(default)]
+    pub priority: i32,
     pub display_name: String,
     pub context_limit: u32,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchKind {
+    #[default]
+    Contains,
+    Exact,
+    Glob,
+    Regex,
+}
+
+impl std::fmt::Display for MatchKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            MatchKind::Contains => "contains",
+            MatchKind::Exact => "exact",
+            MatchKind::Glob => "glob",
+            MatchKind::Regex => "regex",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl ModelEntry {
+    /// Check whether this entry matches a (lowercased) model ID
+    pub fn matches(&self, model_id: &str) -> bool {
+        let pattern = self.pattern.to_lowercase();
+        match self.match_kind {
+            MatchKind::Contains => model_id.contains(&pattern),
+            MatchKind::Exact => model_id == pattern,
+            MatchKind::Glob | MatchKind::Regex => self
+                .compile()
+                .map(|re| re.is_match(model_id))
+                .unwrap_or(false),
+        }
+    }
+
+    /// Regex for glob and regex entries; invalid patterns never match
+    fn compile(&self) -> Result<Regex, regex::Error> {
+        match self.match_kind {
+            MatchKind::Glob => glob_to_regex(&self.pattern.to_lowercase()),
+            _ => RegexBuilder::new(&self.pattern)
+                .case_insensitive(true)
+                .build(),
+        }
+    }
+
+    /// Check that a glob or regex pattern compiles
+    pub fn validate(&self) -> Result<(), regex::Error> {
+        match self.match_kind {
+            MatchKind::Contains | MatchKind::Exact => Ok(()),
+            MatchKind::Glob | MatchKind::Regex => self.compile().map(|_| ()),
+        }
+    }
+}
+
+/// Translate a glob pattern (`*`, `?`, `[...]`) into an anchored regex
+pub(crate) fn glob_to_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    let mut regex = String::from("^");
+    let mut in_class = false;
+
+    for ch in pattern.chars() {
+        match ch {
+            '*' if !in_class => regex.push_str(".*"),
+            '?' if !in_class => regex.push('.'),
+            '[' if !in_class => {
+                in_class = true;
+                regex.push('[');
+            }
+            ']' if in_class => {
+                in_class = false;
+                regex.push(']');
+            }
+            '!' if in_class && regex.ends_with('[') => regex.push('^'),
+            _ if in_class => regex.push(ch),
+            _ => regex.push_str(&regex::escape(&ch.to_string())),
+        }
+    }
+    regex.push('$');
+
+    Regex::new(&regex)
+}
+
 impl ModelConfig {
     /// Load model configuration from TOML file
     pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
@@ -23,6 +112,17 @@ impl ModelConfig {
         Ok(config)
     }
 
+    /// Locations of models.toml, highest precedence first
+    fn config_paths() -> Vec<PathBuf> {
+        [
+            dirs::home_dir().map(|d| d.join(".claude").join("ccline").join("models.toml")),
+            Some(Path::new("models.toml").to_path_buf()),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
     /// Load model configuration with fallback locations
     pub fn load() -> Self {
         let mut model_config = Self::default();
@@ -36,14 +136,9 @@ impl ModelConfig {
         }
 
         // Try loading from user config directory first, then local
-        let config_paths = [
-            dirs::home_dir().map(|d| d.join(".claude").join("ccline").join("models.toml")),
-            Some(Path::new("models.toml").to_path_buf()),
-        ];
-
-        for path in config_paths.iter().flatten() {
+        for path in Self::config_paths() {
             if path.exists() {
-                if let Ok(config) = Self::load_from_file(path) {
+                if let Ok(config) = Self::load_from_file(&path) {
                     // Prepend external models to built-in ones for priority
                     let mut merged_entries = config.model_entries;
                     merged_entries.extend(model_config.model_entries);
@@ -57,45 +152,69 @@ impl ModelConfig {
         model_config
     }
 
-    /// Get context limit for a model based on ID pattern matching
-    /// Checks external config first, then falls back to built-in config
+    /// Like [`Self::load`], but a models.toml that doesn't parse is an error
+    /// instead of being skipped. For `--check` and `models test`.
+    pub fn load_strict() -> Result<Self, Box<dyn std::error::Error>> {
+        for path in Self::config_paths() {
+            if path.exists() {
+                // The first file found is the one `load` uses
+                Self::load_from_file(&path)
+                    .map_err(|e| format!("Invalid {}: {}", path.display(), e))?;
+                break;
+            }
+        }
+        Ok(Self::load())
+    }
+
+    /// Find all entries matching a model ID, best match first
     ///
-    /// Special handling for [1m] suffix: returns 1M context limit for models with this suffix
-    pub fn get_context_limit(&self, model_id: &str) -> u32 {
-        let model_lower = model_id.to_lowercase();
+    /// Entries are ordered by descending `priority`; entries with equal priority
+    /// keep their file order (user entries come before built-in ones).
+    /// The `[1m]` suffix is stripped before matching.
+    pub fn matching_entries(&self, model_id: &str) -> Vec<&ModelEntry> {
+        let match_id = model_id.to_lowercase().replace("[1m]", "");
 
-        // Check if model has [1m] suffix
-        let has_1m = model_lower.contains("[1m]");
+        let mut matches: Vec<&ModelEntry> = self
+            .model_entries
+            .iter()
+            // Skip the generic [1m] pattern to avoid early matching
+            .filter(|entry| entry.pattern != "[1m]")
+            .filter(|entry| entry.matches(&match_id))
+            .collect();
 
-        // If has [1m] suffix, remove it for base model matching
-        let match_id = if has_1m {
-            model_lower.replace("[1m]", "")
-        } else {
-            model_lower.clone()
-        };
+        // Stable sort keeps file order for equal priorities
+        matches.sort_by_key(|entry| std::cmp::Reverse(entry.priority));
+        matches
+    }
 
-        // Match base model (skip the generic [1m] pattern)
-        for entry in &self.model_entries {
-            // Skip the generic [1m] pattern to avoid early matching
-            if entry.pattern == "[1m]" {
-                continue;
-            }
+    /// Entries whose pattern doesn't compile, with the reason
+    pub fn invalid_entries(&self) -> Vec<(&ModelEntry, regex::Error)> {
+        self.model_entries
+            .iter()
+            .filter_map(|entry| entry.validate().err().map(|e| (entry, e)))
+            .collect()
+    }
 
-            if match_id.contains(&entry.pattern.to_lowercase()) {
-                return if has_1m {
-                    1_000_000 // Override with 1M context for [1m] suffix
-                } else {
-                    entry.context_limit
-                };
-            }
-        }
+    /// Find the entry that wins for a model ID
+    pub fn find_entry(&self, model_id: &str) -> Option<&ModelEntry> {
+        self.matching_entries(model_id).into_iter().next()
+    }
+
+    /// Get context limit for a model based on ID pattern matching
+    /// Checks external config first, then falls back to built-in config
+    ///
+    /// Special handling for [1m] suffix: returns 1M context limit for models with this suffix
+    pub fn get_context_limit(&self, model_id: &str) -> u32 {
+        let has_1m = model_id.to_lowercase().contains("[1m]");
 
-        // If no match found but has [1m] suffix, return 1M context
         if has_1m {
+            // Override with 1M context for [1m] suffix
             return 1_000_000;
         }
 
-        200_000
+        self.find_entry(model_id)
+            .map(|entry| entry.context_limit)
+            .unwrap_or(200_000)
     }
 
     /// Get display name for a model based on ID pattern matching
@@ -104,41 +223,15 @@ impl ModelConfig {
     ///
     /// Special handling for [1m] suffix: automatically appends " 1M" to the base model name
     pub fn get_display_name(&self, model_id: &str) -> Option<String> {
-        let model_lower = model_id.to_lowercase();
-
-        // Check if model has [1m] suffix
-        let has_1m = model_lower.contains("[1m]");
+        let has_1m = model_id.to_lowercase().contains("[1m]");
 
-        // If has [1m] suffix, remove it for base model matching
-        let match_id = if has_1m {
-            model_lower.replace("[1m]", "")
-        } else {
-            model_lower.clone()
-        };
-
-        // Match base model (skip the generic [1m] pattern)
-        for entry in &self.model_entries {
-            // Skip the generic [1m] pattern to avoid early matching
-            if entry.pattern == "[1m]" {
-                continue;
-            }
-
-            if match_id.contains(&entry.pattern.to_lowercase()) {
-                let base_name = entry.display_name.clone();
-                return Some(if has_1m {
-                    format!("{} 1M", base_name) // Append " 1M" suffix
-                } else {
-                    base_name
-                });
-            }
+        match self.find_entry(model_id) {
+            Some(entry) if has_1m => Some(format!("{} 1M", entry.display_name)),
+            Some(entry) => Some(entry.display_name.clone()),
+            // If no match found but has [1m] suffix, use generic 1M display
+            None if has_1m => Some("Sonnet 4 1M".to_string()),
+            None => None,
         }
-
-        // If no match found but has [1m] suffix, use generic 1M display
-        if has_1m {
-            return Some("Sonnet 4 1M".to_string());
-        }
-
-        None
     }
 
     /// Create default model configuration file with minimal template
@@ -160,13 +253,22 @@ impl ModelConfig {
              \n\
              # Model configurations\n\
              # Each [[models]] section defines a model pattern and its properties\n\
-             # Order matters: first match wins, so put more specific patterns first\n\
+             # match = \"contains\" (default) | \"exact\" | \"glob\" | \"regex\"\n\
+             # Higher priority wins; entries with equal priority are tried in file order\n\
+             # Run `ccline models test <model-id>` to see which entry matches\n\
              \n\
              # Example of how to add new models:\n\
              # [[models]]\n\
              # pattern = \"glm-4.5\"\n\
              # display_name = \"GLM-4.5\"\n\
-             # context_limit = 128000\n",
+             # context_limit = 128000\n\
+             #\n\
+             # [[models]]\n\
+             # pattern = \"^claude-sonnet-4-5-\\\\d{{8}}$\"\n\
+             # match = \"regex\"\n\
+             # priority = 10\n\
+             # display_name = \"Sonnet 4.5\"\n\
+             # context_limit = 200000\n",
             toml_content.trim()
         );
 
@@ -187,75 +289,103 @@ impl Default for ModelConfig {
                 // Sonnet 4.5 (more specific pattern, must come before sonnet-4)
                 ModelEntry {
                     pattern: "claude-sonnet-4-5".to_string(),
+                    match_kind: MatchKind::Contains,
+                    priority: 0,
                     display_name: "Sonnet 4.5".to_string(),
                     context_limit: 200_000,
                 },
                 ModelEntry {
                     pattern: "sonnet-4-5".to_string(),
+                    match_kind: MatchKind::Contains,
+                    priority: 0,
                     display_name: "Sonnet 4.5".to_string(),
                     context_limit: 200_000,
                 },
                 // Sonnet 4 (more general pattern, must come after 4.5)
                 ModelEntry {
                     pattern: "claude-sonnet-4".to_string(),
+                    match_kind: MatchKind::Contains,
+                    priority: 0,
                     display_name: "Sonnet 4".to_string(),
                     context_limit: 200_000,
                 },
                 ModelEntry {
                     pattern: "claude-4-sonnet".to_string(),
+                    match_kind: MatchKind::Contains,
+                    priority: 0,
                     display_name: "Sonnet 4".to_string(),
                     context_limit: 200_000,
                 },
                 // Opus 4.6 (more specific pattern, must come before opus-4)
                 ModelEntry {
                     pattern: "claude-opus-4-6".to_string(),
+                    match_kind: MatchKind::Contains,
+                    priority: 0,
                     display_name: "Opus 4.6".to_string(),
                     context_limit: 200_000,
                 },
                 ModelEntry {
                     pattern: "opus-4-6".to_string(),
+                    match_kind: MatchKind::Contains,
+                    priority: 0,
                     display_name: "Opus 4.6".to_string(),
                     context_limit: 200_000,
                 },
                 // Opus 4 (generic patterns)
                 ModelEntry {
                     pattern: "claude-opus-4".to_string(),
+                    match_kind: MatchKind::Contains,
+                    priority: 0,
                     display_name: "Opus 4".to_string(),
                     context_limit: 200_000,
                 },
                 ModelEntry {
                     pattern: "claude-4-opus".to_string(),
+                    match_kind: MatchKind::Contains,
+                    priority: 0,
                     display_name: "Opus 4".to_string(),
                     context_limit: 200_000,
                 },
                 ModelEntry {
                     pattern: "sonnet-4".to_string(),
+                    match_kind: MatchKind::Contains,
+                    priority: 0,
                     display_name: "Sonnet 4".to_string(),
                     context_limit: 200_000,
                 },
                 ModelEntry {
                     pattern: "claude-3-7-sonnet".to_string(),
+                    match_kind: MatchKind::Contains,
+                    priority: 0,
                     display_name: "Sonnet 3.7".to_string(),
                     context_limit: 200_000,
                 },
                 // Third-party models
                 ModelEntry {
                     pattern: "glm-4.5".to_string(),
+                    match_kind: MatchKind::Contains,
+                    priority: 0,
                     display_name: "GLM-4.5".to_string(),
                     context_limit: 128_000,
                 },
                 ModelEntry {
                     pattern: "kimi-k2-turbo".to_string(),
+                    match_kind: MatchKind::Contains,
+                    priority: 0,
                     display_name: "Kimi K2 Turbo".to_string(),
                     context_limit: 128_000,
                 },
                 ModelEntry {
                     pattern: "kimi-k2".to_string(),
+                    match_kind: MatchKind::Contains,
+                    priority: 0,
                     display_name: "Kimi K2".to_string(),
                     context_limit: 128_000,
                 },
                 ModelEntry {
                     pattern: "qwen3-coder".to_string(),
+                    match_kind: MatchKind::Contains,
+                    priority: 0,
                     display_name: "Qwen Coder".to_string(),
                     context_limit: 256_000,
                 },
@@ -264,6 +394,8 @@ impl Default for ModelConfig {
                 // The actual [1m] detection is handled by get_display_name and get_context_limit
                 ModelEntry {
                     pattern: "[1m]".to_string(),
+                    match_kind: MatchKind::Contains,
+                    priority: 0,
                     display_name: "Sonnet 4 1M".to_string(),
                     context_limit: 1_000_000,
                 },
@@ -274,7 +406,17 @@ impl Default for ModelConfig {
 
 #[cfg(test)]
 mod tests {
-    use super::ModelConfig;
+    use super::{MatchKind, ModelConfig, ModelEntry};
+
+    fn entry(pattern: &str, match_kind: MatchKind, priority: i32, name: &str) -> ModelEntry {
+        ModelEntry {
+            pattern: pattern.to_string(),
+            match_kind,
+            priority,
+            display_name: name.to_string(),
+            context_limit: 100_000,
+        }
+    }
 
     #[test]
     fn maps_opus_4_6_model_name() {
@@ -289,4 +431,53 @@ mod tests {
         let display_name = config.get_display_name("claude-opus-4-6-20260101[1m]");
         assert_eq!(display_name, Some("Opus 4.6 1M".to_string()));
     }
+
+    #[test]
+    fn priority_overrides_file_order() {
+        let config = ModelConfig {
+            model_entries: vec![
+                entry("sonnet", MatchKind::Contains, 0, "Sonnet"),
+                entry("sonnet-4-5", MatchKind::Contains, 10, "Sonnet 4.5"),
+            ],
+        };
+        assert_eq!(
+            config.get_display_name("claude-sonnet-4-5-20250929"),
+            Some("Sonnet 4.5".to_string())
+        );
+        assert_eq!(config.get_display_name("claude-sonnet-4"), Some("Sonnet".to_string()));
+    }
+
+    #[test]
+    fn supports_exact_glob_and_regex_matching() {
+        let config = ModelConfig {
+            model_entries: vec![
+                entry("my-model", MatchKind::Exact, 0, "Exact"),
+                entry("glm-4.?-*", MatchKind::Glob, 0, "Glob"),
+                entry(r"^qwen\d+-coder$", MatchKind::Regex, 0, "Regex"),
+            ],
+        };
+        assert_eq!(config.get_display_name("My-Model"), Some("Exact".to_string()));
+        assert_eq!(config.get_display_name("my-model-2"), None);
+        assert_eq!(config.get_display_name("glm-4.5-air"), Some("Glob".to_string()));
+        assert_eq!(config.get_display_name("glm-4.5"), None);
+        assert_eq!(config.get_display_name("Qwen3-Coder"), Some("Regex".to_string()));
+    }
+
+    #[test]
+    fn reports_invalid_glob_and_regex_patterns() {
+        let config = ModelConfig {
+            model_entries: vec![
+                entry("glm-[4", MatchKind::Glob, 0, "Glob"),
+                entry("qwen(", MatchKind::Regex, 0, "Regex"),
+                entry("qwen(", MatchKind::Contains, 0, "Contains"),
+                entry("glm-4.?", MatchKind::Glob, 0, "Valid"),
+            ],
+        };
+        let invalid: Vec<&str> = config
+            .invalid_entries()
+            .iter()
+            .map(|(entry, _)| entry.display_name.as_str())
+            .collect();
+        assert_eq!(invalid, ["Glob", "Regex"]);
+    }
 }
@@ -0,0 +1,371 @@
+//! Project-local configuration layered over the user config.
+//!
+//! Precedence, lowest first:
+//! 1. `~/.claude/ccline/config.toml` (or the built-in default)
+//! 2. The theme: `--theme`, else the nearest project file's `theme`. It
+//!    replaces style and segments unless it is the user config's own theme.
+//! 3. Project files from the outermost directory inwards, `.claude/ccline.toml`
+//!    before `.ccline.toml` within one directory
+//!
+//! Project files hold any subset of the config. Tables merge key by key, and
+//! `[[segments]]` entries merge into the segment with the same key (name,
+//! plugin name or id); segments not in the config are appended.
+//!
+//! Custom, plugin and script segments run code on every render, so a project
+//! file may only add them, or change what they run, if its directory is in
+//! the user config's `trusted_projects`.
+
+use super::types::{Config, SegmentId};
+use crate::ui::themes::ThemePresets;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+/// Project config file names, relative to each directory, lowest precedence first
+pub const PROJECT_CONFIG_FILES: [&str; 2] = [".claude/ccline.toml", ".ccline.toml"];
+
+/// Project config files applying to `dir`, lowest precedence first
+pub fn find_project_configs(dir: &Path) -> Vec<PathBuf> {
+    let mut ancestors: Vec<&Path> = dir.ancestors().collect();
+    ancestors.reverse();
+
+    ancestors
+        .into_iter()
+        .flat_map(|ancestor| {
+            PROJECT_CONFIG_FILES
+                .iter()
+                .map(move |name| ancestor.join(name))
+        })
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// Options choosing what a custom, plugin or script segment runs
+const CODE_OPTIONS: [&str; 5] = ["command", "plugin", "script", "script_file", "shell"];
+
+/// Directory a project file belongs to: the one holding `.ccline.toml` or `.claude/`
+fn project_dir(path: &Path) -> &Path {
+    let dir = path.parent().unwrap_or(path);
+    if path.ends_with(PROJECT_CONFIG_FILES[0]) {
+        dir.parent().unwrap_or(dir)
+    } else {
+        dir
+    }
+}
+
+/// Whether `dir` is at or below one of the trusted directories
+fn is_trusted(dir: &Path, trusted_projects: &[String]) -> bool {
+    let canonical = |path: PathBuf| path.canonicalize().unwrap_or(path);
+    let dir = canonical(dir.to_path_buf());
+
+    trusted_projects.iter().any(|trusted| {
+        let trusted = match trusted.strip_prefix("~/") {
+            Some(rest) => match dirs::home_dir() {
+                Some(home) => home.join(rest),
+                None => return false,
+            },
+            None => PathBuf::from(trusted),
+        };
+        dir.starts_with(canonical(trusted))
+    })
+}
+
+/// Refuse a merge from an untrusted layer that added a code-running segment or
+/// changed what an existing one runs
+fn check_untrusted(base: &Config, merged: &Config) -> Result<(), String> {
+    let runs_code = |id: SegmentId| {
+        matches!(
+            id,
+            SegmentId::Custom | SegmentId::Plugin | SegmentId::Script
+        )
+    };
+
+    for segment in merged
+        .segments
+        .iter()
+        .filter(|segment| runs_code(segment.id))
+    {
+        let unchanged = base.segments.iter().any(|existing| {
+            existing.id == segment.id
+                && existing.key() == segment.key()
+                && CODE_OPTIONS
+                    .iter()
+                    .all(|option| existing.options.get(*option) == segment.options.get(*option))
+        });
+        if !unchanged {
+            return Err(format!(
+                "segment {} runs code; add the project to trusted_projects in the user config to allow it",
+                segment.key()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Same key as `SegmentConfig::key`, read from an unparsed segment table
+fn segment_key(segment: &Table) -> Option<String> {
+    let field = |name: &str| segment.get(name).and_then(Value::as_str);
+
+    if let Some(name) = field("name") {
+        return Some(name.to_string());
+    }
+    if field("id") == Some("plugin") {
+        let plugin = segment
+            .get("options")
+            .and_then(|options| options.get("plugin"))
+            .and_then(Value::as_str);
+        if let Some(plugin) = plugin {
+            return Some(plugin.to_string());
+        }
+    }
+    field("id").map(str::to_string)
+}
+
+/// Merge `overlay` into `base`: tables recursively, everything else replaced
+fn merge_tables(base: &mut Table, overlay: Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base_table)), Value::Table(table)) => {
+                merge_tables(base_table, table)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn merge_segments(base: &mut Vec<Value>, overlay: Vec<Value>) {
+    for segment in overlay {
+        let Value::Table(segment) = segment else {
+            continue;
+        };
+        let key = segment_key(&segment);
+
+        let existing = base.iter_mut().find_map(|value| match value {
+            Value::Table(table) if key.is_some() && segment_key(table) == key => Some(table),
+            _ => None,
+        });
+        match existing {
+            Some(table) => merge_tables(table, segment),
+            None => {
+                // New segments only need an id; everything else starts empty
+                let mut table: Table = toml::from_str(
+                    "enabled = true\nicon = { plain = \"\", nerd_font = \"\" }\ncolors = {}\nstyles = { text_bold = false }\noptions = {}",
+                )
+                .unwrap_or_default();
+                merge_tables(&mut table, segment);
+                base.push(Value::Table(table));
+            }
+        }
+    }
+}
+
+/// Merge one project file over a config. Only a trusted layer may add or
+/// change segments that run code.
+fn apply_layer(
+    config: &Config,
+    mut layer: Table,
+    trusted: bool,
+) -> Result<Config, Box<dyn std::error::Error>> {
+    // Trust is granted by the user config alone
+    layer.remove("trusted_projects");
+    let mut merged = Table::try_from(config)?;
+
+    if let Some(Value::Array(segments)) = layer.remove("segments") {
+        if let Some(Value::Array(base)) = merged.get_mut("segments") {
+            merge_segments(base, segments);
+        }
+    }
+    merge_tables(&mut merged, layer);
+
+    let merged: Config = merged.try_into()?;
+    if !trusted {
+        check_untrusted(config, &merged)?;
+    }
+    Ok(merged)
+}
+
+/// Apply the theme a project file names. The name must be a plain theme
+/// name, and an untrusted project may only pick a theme that runs no code
+/// beyond what the user config already runs.
+fn apply_project_theme(
+    config: &Config,
+    theme: &str,
+    path: &Path,
+    themed: impl FnOnce(&str) -> Config,
+) -> Result<Config, Box<dyn std::error::Error>> {
+    if !ThemePresets::is_valid_theme_name(theme) {
+        return Err(format!("Invalid theme name: {}", theme).into());
+    }
+    let merged = themed(theme);
+    if !is_trusted(project_dir(path), &config.trusted_projects) {
+        check_untrusted(config, &merged).map_err(|e| format!("theme {}: {}", theme, e))?;
+    }
+    Ok(merged)
+}
+
+impl Config {
+    /// User config with the theme override and the project files for `dir`
+    /// applied. Returns the config and the project files that were merged.
+    pub fn load_layered(
+        dir: &Path,
+        theme: Option<&str>,
+    ) -> Result<(Config, Vec<PathBuf>), Box<dyn std::error::Error>> {
+        let mut config = Config::load()?;
+
+        let paths = find_project_configs(dir);
+        let mut layers = Vec::with_capacity(paths.len());
+        for path in &paths {
+            let content = fs::read_to_string(path)?;
+            let layer: Table = toml::from_str(&content)
+                .map_err(|e| format!("Invalid project config {}: {}", path.display(), e))?;
+            layers.push(layer);
+        }
+
+        let project_theme = layers
+            .iter_mut()
+            .zip(&paths)
+            .filter_map(|(layer, path)| layer.remove("theme").map(|theme| (theme, path)))
+            .last()
+            .and_then(|(theme, path)| theme.as_str().map(|theme| (theme.to_string(), path)));
+
+        // Color mode, glyph widths and trust describe this machine, not the theme
+        if let Some(theme) = theme {
+            config = config.themed(theme);
+        } else if let Some((theme, path)) = project_theme {
+            if theme != config.theme {
+                config =
+                    apply_project_theme(&config, &theme, path, |theme| config.themed(theme))
+                        .map_err(|e| format!("Invalid project config {}: {}", path.display(), e))?;
+            }
+        }
+
+        for (path, layer) in paths.iter().zip(layers) {
+            let trusted = is_trusted(project_dir(path), &config.trusted_projects);
+            config = apply_layer(&config, layer, trusted)
+                .map_err(|e| format!("Invalid project config {}: {}", path.display(), e))?;
+        }
+
+        Ok((config, paths))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn project_layer_merges_segments_by_key() {
+        let config = Config::default();
+        let layer: Table = toml::from_str(
+            r#"
+            [style]
+            separator = " / "
+
+            [[segments]]
+            id = "git"
+            enabled = false
+
+            [[segments]]
+            id = "kubernetes"
+            [segments.options]
+            show_namespace = true
+            "#,
+        )
+        .unwrap();
+
+        let merged = apply_layer(&config, layer, false).unwrap();
+        let git = merged.segments.iter().find(|s| s.key() == "git").unwrap();
+        let kubernetes = merged.segments.last().unwrap();
+
+        assert_eq!(merged.style.separator, " / ");
+        assert_eq!(merged.style.mode, config.style.mode);
+        assert!(!git.enabled);
+        assert_eq!(git.colors.icon, config.segments[2].colors.icon);
+        assert_eq!(merged.segments.len(), config.segments.len() + 1);
+        assert_eq!(kubernetes.key(), "kubernetes");
+        assert!(kubernetes.enabled);
+        assert_eq!(kubernetes.options["show_namespace"], true);
+    }
+
+    #[test]
+    fn untrusted_layers_cannot_add_or_change_code_running_segments() {
+        let mut config = Config::default();
+        let custom: Table = toml::from_str(
+            r#"
+            [[segments]]
+            id = "custom"
+            [segments.options]
+            command = "date"
+            "#,
+        )
+        .unwrap();
+        config = apply_layer(&config, custom.clone(), true).unwrap();
+
+        let layer = |toml: &str| toml::from_str::<Table>(toml).unwrap();
+        let add_plugin = layer("[[segments]]\nid = \"plugin\"\noptions = { plugin = \"x\" }");
+        let change_command =
+            layer("[[segments]]\nid = \"custom\"\noptions = { command = \"touch pwned\" }");
+        let disable = layer("[[segments]]\nid = \"custom\"\nenabled = false");
+        let self_trust = layer("trusted_projects = [\"/\"]");
+
+        assert!(apply_layer(&config, add_plugin.clone(), false).is_err());
+        assert!(apply_layer(&config, change_command, false).is_err());
+        assert!(apply_layer(&config, add_plugin, true).is_ok());
+        assert!(
+            !apply_layer(&config, disable, false)
+                .unwrap()
+                .segments
+                .last()
+                .unwrap()
+                .enabled
+        );
+        assert!(apply_layer(&config, self_trust, false)
+            .unwrap()
+            .trusted_projects
+            .is_empty());
+    }
+
+    #[test]
+    fn project_themes_must_be_plain_names_and_respect_trust() {
+        let config = Config::default();
+        let path = Path::new("/tmp/proj/.ccline.toml");
+        let runs_code = |_: &str| {
+            let custom: Table = toml::from_str(
+                "[[segments]]\nid = \"custom\"\noptions = { command = \"touch pwned\" }",
+            )
+            .unwrap();
+            apply_layer(&Config::default(), custom, true).unwrap()
+        };
+
+        for theme in ["/tmp/proj/evil", "../evil", "a.b", ""] {
+            assert!(apply_project_theme(&config, theme, path, |_| unreachable!()).is_err());
+        }
+        assert!(apply_project_theme(&config, "evil", path, runs_code).is_err());
+        assert!(apply_project_theme(&config, "nord", path, |_| Config::default()).is_ok());
+
+        let trusted = Config {
+            trusted_projects: vec!["/tmp/proj".to_string()],
+            ..Config::default()
+        };
+        assert!(apply_project_theme(&trusted, "evil", path, runs_code).is_ok());
+    }
+
+    #[test]
+    fn trust_covers_project_directories_below_trusted_paths() {
+        let trusted = vec!["/work".to_string()];
+
+        assert_eq!(
+            project_dir(Path::new("/work/app/.claude/ccline.toml")),
+            Path::new("/work/app")
+        );
+        assert_eq!(
+            project_dir(Path::new("/work/.ccline.toml")),
+            Path::new("/work")
+        );
+        assert!(is_trusted(Path::new("/work/app"), &trusted));
+        assert!(!is_trusted(Path::new("/workshop"), &trusted));
+        assert!(!is_trusted(Path::new("/tmp/proj"), &trusted));
+    }
+}
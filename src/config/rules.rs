@@ -0,0 +1,387 @@
+//! `show_when` / `hide_when` visibility rules.
+//!
+//! A rule compares segment values against literals, e.g.
+//! `status == "Clean" && ahead == 0 && behind == 0` or `cost > 0.5`.
+//! Identifiers are metadata keys of the segment, `primary`, `secondary`,
+//! or `env.NAME` for environment variables. Missing values are empty.
+
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleError(String);
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for RuleError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Matches,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Literal(String),
+    Op(CompareOp),
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Value(String),
+    Literal(String),
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(Operand, CompareOp, Operand),
+    Truthy(Operand),
+}
+
+/// A parsed visibility rule
+#[derive(Debug, Clone)]
+pub struct Rule {
+    expr: Expr,
+}
+
+impl Rule {
+    pub fn parse(source: &str) -> Result<Self, RuleError> {
+        let tokens = tokenize(source)?;
+        if tokens.is_empty() {
+            return Err(RuleError("empty rule".to_string()));
+        }
+
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.parse_or()?;
+        if parser.pos < parser.tokens.len() {
+            return Err(RuleError(format!(
+                "unexpected {:?} at token {}",
+                parser.tokens[parser.pos],
+                parser.pos + 1
+            )));
+        }
+
+        if let Some(pattern) = invalid_regex(&expr) {
+            return Err(RuleError(format!("invalid regex \"{}\"", pattern)));
+        }
+
+        Ok(Self { expr })
+    }
+
+    /// Evaluate against segment values (metadata plus `primary`/`secondary`)
+    pub fn evaluate(&self, values: &HashMap<String, String>) -> bool {
+        eval(&self.expr, values)
+    }
+}
+
+/// Decide visibility from optional `show_when` and `hide_when` rules.
+/// Rules that fail to parse are ignored; `Config::check` reports them.
+pub fn is_visible(
+    show_when: Option<&str>,
+    hide_when: Option<&str>,
+    values: &HashMap<String, String>,
+) -> bool {
+    let holds = |rule: Option<&str>| {
+        rule.and_then(|source| Rule::parse(source).ok())
+            .map(|rule| rule.evaluate(values))
+    };
+
+    holds(show_when).unwrap_or(true) && !holds(hide_when).unwrap_or(false)
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, RuleError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            '&' if next == Some('&') => {
+                tokens.push(Token::And);
+                i += 2;
+            }
+            '|' if next == Some('|') => {
+                tokens.push(Token::Or);
+                i += 2;
+            }
+            '=' if next == Some('=') => {
+                tokens.push(Token::Op(CompareOp::Eq));
+                i += 2;
+            }
+            '=' if next == Some('~') => {
+                tokens.push(Token::Op(CompareOp::Matches));
+                i += 2;
+            }
+            '!' if next == Some('=') => {
+                tokens.push(Token::Op(CompareOp::Ne));
+                i += 2;
+            }
+            '!' => {
+                tokens.push(Token::Not);
+                i += 1;
+            }
+            '>' | '<' => {
+                let op = match (c, next == Some('=')) {
+                    ('>', true) => CompareOp::Ge,
+                    ('>', false) => CompareOp::Gt,
+                    ('<', true) => CompareOp::Le,
+                    _ => CompareOp::Lt,
+                };
+                tokens.push(Token::Op(op));
+                i += if next == Some('=') { 2 } else { 1 };
+            }
+            '"' | '\'' => {
+                let quote = c;
+                let mut literal = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        Some('\\') if chars.get(i + 1).is_some() => {
+                            literal.push(chars[i + 1]);
+                            i += 2;
+                        }
+                        Some(&ch) if ch == quote => {
+                            i += 1;
+                            break;
+                        }
+                        Some(&ch) => {
+                            literal.push(ch);
+                            i += 1;
+                        }
+                        None => return Err(RuleError("unterminated string".to_string())),
+                    }
+                }
+                tokens.push(Token::Literal(literal));
+            }
+            c if c.is_ascii_digit() || (c == '-' && next.is_some_and(|n| n.is_ascii_digit())) => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                tokens.push(Token::Literal(chars[start..i].iter().collect()));
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || matches!(chars[i], '_' | '.' | '-'))
+                {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                tokens.push(match word.as_str() {
+                    "true" | "false" => Token::Literal(word),
+                    _ => Token::Ident(word),
+                });
+            }
+            other => return Err(RuleError(format!("unexpected character '{}'", other))),
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, RuleError> {
+        let mut expr = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, RuleError> {
+        let mut expr = self.parse_unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            expr = Expr::And(Box::new(expr), Box::new(self.parse_unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, RuleError> {
+        match self.peek() {
+            Some(Token::Not) => {
+                self.pos += 1;
+                Ok(Expr::Not(Box::new(self.parse_unary()?)))
+            }
+            Some(Token::LParen) => {
+                self.pos += 1;
+                let expr = self.parse_or()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(expr),
+                    _ => Err(RuleError("missing ')'".to_string())),
+                }
+            }
+            _ => self.parse_comparison(),
+        }
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, RuleError> {
+        let left = self.parse_operand()?;
+        if let Some(Token::Op(op)) = self.peek().cloned() {
+            self.pos += 1;
+            let right = self.parse_operand()?;
+            Ok(Expr::Compare(left, op, right))
+        } else {
+            Ok(Expr::Truthy(left))
+        }
+    }
+
+    fn parse_operand(&mut self) -> Result<Operand, RuleError> {
+        match self.next() {
+            Some(Token::Ident(name)) => Ok(Operand::Value(name)),
+            Some(Token::Literal(value)) => Ok(Operand::Literal(value)),
+            Some(token) => Err(RuleError(format!("expected a value, found {:?}", token))),
+            None => Err(RuleError("unexpected end of rule".to_string())),
+        }
+    }
+}
+
+fn invalid_regex(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::And(a, b) | Expr::Or(a, b) => invalid_regex(a).or_else(|| invalid_regex(b)),
+        Expr::Not(inner) => invalid_regex(inner),
+        Expr::Compare(_, CompareOp::Matches, Operand::Literal(pattern)) => {
+            Regex::new(pattern).is_err().then(|| pattern.clone())
+        }
+        _ => None,
+    }
+}
+
+fn resolve(operand: &Operand, values: &HashMap<String, String>) -> String {
+    match operand {
+        Operand::Literal(value) => value.clone(),
+        Operand::Value(name) => match name.strip_prefix("env.") {
+            Some(var) => std::env::var(var).unwrap_or_default(),
+            None => values.get(name).cloned().unwrap_or_default(),
+        },
+    }
+}
+
+fn eval(expr: &Expr, values: &HashMap<String, String>) -> bool {
+    match expr {
+        Expr::And(a, b) => eval(a, values) && eval(b, values),
+        Expr::Or(a, b) => eval(a, values) || eval(b, values),
+        Expr::Not(inner) => !eval(inner, values),
+        Expr::Truthy(operand) => {
+            let value = resolve(operand, values);
+            !(value.is_empty() || value == "0" || value.eq_ignore_ascii_case("false"))
+        }
+        Expr::Compare(left, op, right) => {
+            let left = resolve(left, values);
+            let right = resolve(right, values);
+            compare(&left, *op, &right)
+        }
+    }
+}
+
+fn compare(left: &str, op: CompareOp, right: &str) -> bool {
+    if op == CompareOp::Matches {
+        return Regex::new(right)
+            .map(|re| re.is_match(left))
+            .unwrap_or(false);
+    }
+
+    // Numeric comparison when both sides are numbers, e.g. `cost > 0.5`
+    if let (Ok(l), Ok(r)) = (left.trim().parse::<f64>(), right.trim().parse::<f64>()) {
+        return match op {
+            CompareOp::Eq => l == r,
+            CompareOp::Ne => l != r,
+            CompareOp::Gt => l > r,
+            CompareOp::Ge => l >= r,
+            CompareOp::Lt => l < r,
+            CompareOp::Le => l <= r,
+            CompareOp::Matches => unreachable!(),
+        };
+    }
+
+    match op {
+        CompareOp::Eq => left.eq_ignore_ascii_case(right),
+        CompareOp::Ne => !left.eq_ignore_ascii_case(right),
+        // Ordering only makes sense for numbers
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn evaluates_comparisons_and_logic() {
+        let git = values(&[("status", "Clean"), ("ahead", "0"), ("behind", "2")]);
+        let rule = Rule::parse(r#"status == "Clean" && ahead == 0 && behind == 0"#).unwrap();
+        assert!(!rule.evaluate(&git));
+
+        let rule = Rule::parse(r#"!(status != "clean") && (ahead > 0 || behind >= 1)"#).unwrap();
+        assert!(rule.evaluate(&git));
+
+        let cost = values(&[("cost", "0.75")]);
+        assert!(Rule::parse("cost > 0.5").unwrap().evaluate(&cost));
+        assert!(!Rule::parse("missing").unwrap().evaluate(&cost));
+        assert!(Rule::parse(r#"cost =~ "^0\\.7""#).unwrap().evaluate(&cost));
+    }
+
+    #[test]
+    fn rejects_malformed_rules() {
+        for source in ["", "status ==", "(a == 1", "a == 'x", "a =~ \"[\"", "a == 1 b"] {
+            assert!(Rule::parse(source).is_err(), "{} should not parse", source);
+        }
+    }
+}
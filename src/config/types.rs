@@ -2,50 +2,35 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 // Usage Segment specific configuration types
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum UsageDisplayFormat {
     Percentage,
     Tokens,
+    #[default]
     Both,
     Bar,
 }
 
-impl Default for UsageDisplayFormat {
-    fn default() -> Self {
-        UsageDisplayFormat::Both
-    }
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum TokenUnit {
+    #[default]
     Auto,
     K,
     Raw,
 }
 
-impl Default for TokenUnit {
-    fn default() -> Self {
-        TokenUnit::Auto
-    }
-}
-
 // Directory Segment specific configuration types
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum CaseStyle {
+    #[default]
     Original,
     Lowercase,
     Uppercase,
 }
 
-impl Default for CaseStyle {
-    fn default() -> Self {
-        CaseStyle::Original
-    }
-}
-
 // Directory Segment configuration helper
 #[derive(Debug, Clone)]
 pub struct DirectorySegmentConfig {
@@ -54,6 +39,16 @@ pub struct DirectorySegmentConfig {
     pub abbreviate_home: bool,
     pub show_parent: bool,
     pub case_style: CaseStyle,
+    /// Render `project-name/relative/path` from the detected project root
+    pub show_project_root: bool,
+    /// Files or directories marking a project root when there is no VCS root
+    pub project_markers: Vec<String>,
+    /// Shorten intermediate components to their shortest unique prefix (fish-style)
+    pub abbreviate_components: bool,
+    /// Trailing components kept in full when abbreviating
+    pub full_components: usize,
+    /// Link the path to its `file://` URL (OSC 8)
+    pub hyperlink: bool,
 }
 
 impl Default for DirectorySegmentConfig {
@@ -64,6 +59,23 @@ impl Default for DirectorySegmentConfig {
             abbreviate_home: true,
             show_parent: false,
             case_style: CaseStyle::Original,
+            show_project_root: false,
+            project_markers: [
+                "Cargo.toml",
+                "package.json",
+                "pyproject.toml",
+                "go.mod",
+                "pom.xml",
+                "build.gradle",
+                "Gemfile",
+                "composer.json",
+            ]
+            .iter()
+            .map(|marker| marker.to_string())
+            .collect(),
+            abbreviate_components: false,
+            full_components: 1,
+            hyperlink: false,
         }
     }
 }
@@ -74,7 +86,7 @@ impl DirectorySegmentConfig {
         
         config.max_length = options.get("max_length")
             .and_then(|v| v.as_u64())
-            .map(|v| v.max(5).min(100) as usize)
+            .map(|v| v.clamp(5, 100) as usize)
             .unwrap_or(config.max_length);
             
         config.show_full_path = options.get("show_full_path")
@@ -94,23 +106,71 @@ impl DirectorySegmentConfig {
                 config.case_style = case_style;
             }
         }
+
+        config.show_project_root = options.get("show_project_root")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(config.show_project_root);
+
+        config.project_markers = options.get("project_markers")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or(config.project_markers);
+
+        config.abbreviate_components = options.get("abbreviate_components")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(config.abbreviate_components);
+
+        config.full_components = options.get("full_components")
+            .and_then(|v| v.as_u64())
+            .map(|v| v.clamp(1, 10) as usize)
+            .unwrap_or(config.full_components);
+
+        config.hyperlink = options.get("hyperlink")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(config.hyperlink);
         
         config
     }
 }
 
 // Git Segment specific configuration types
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum GitStatusFormat {
+    #[default]
     Symbols,
     Text,
     Count,
 }
 
-impl Default for GitStatusFormat {
-    fn default() -> Self {
-        GitStatusFormat::Symbols
+/// Which uncommitted changes the Git diff line statistics cover
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffStatsScope {
+    /// Everything that differs from HEAD, like `git diff --shortstat HEAD`
+    #[default]
+    All,
+    /// Only files touched by Edit/Write tool calls in the current session
+    Session,
+}
+
+/// Version control systems the Git segment can report on
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum VcsKind {
+    #[serde(rename = "git")]
+    Git,
+    #[serde(rename = "jj")]
+    Jujutsu,
+    #[serde(rename = "hg")]
+    Mercurial,
+}
+
+impl VcsKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            VcsKind::Git => "git",
+            VcsKind::Jujutsu => "jj",
+            VcsKind::Mercurial => "hg",
+        }
     }
 }
 
@@ -125,6 +185,29 @@ pub struct GitSegmentConfig {
     pub hide_clean_status: bool,
     pub branch_max_length: usize,
     pub status_format: GitStatusFormat,
+    /// Count staged, unstaged, untracked and renamed files instead of added/modified/deleted
+    pub status_breakdown: bool,
+    pub show_state: bool,
+    pub show_worktree: bool,
+    /// Colors per in-progress operation ("rebase", "merge", "cherry_pick", "revert", "bisect", "am")
+    pub state_colors: HashMap<String, ColorConfig>,
+    pub show_diff_stats: bool,
+    pub diff_stats_scope: DiffStatsScope,
+    pub show_commit_age: bool,
+    pub show_commit_subject: bool,
+    pub show_commit_author: bool,
+    pub subject_max_length: usize,
+    /// Minutes after the last commit at which uncommitted work counts as stale (0 disables)
+    pub stale_after_minutes: u64,
+    pub stale_colors: Option<ColorConfig>,
+    /// Show the upstream ref name, or indicators when it is missing or gone
+    pub show_upstream: bool,
+    /// Branch to report ahead/behind against, e.g. "main"
+    pub base_branch: Option<String>,
+    /// VCS to look for; earlier entries win when a directory has several (jj colocated with git)
+    pub vcs_order: Vec<VcsKind>,
+    /// Link the branch to its page on the `origin` remote's website (OSC 8)
+    pub hyperlink: bool,
 }
 
 impl Default for GitSegmentConfig {
@@ -138,6 +221,22 @@ impl Default for GitSegmentConfig {
             hide_clean_status: false,
             branch_max_length: 15,
             status_format: GitStatusFormat::Symbols,
+            status_breakdown: false,
+            show_state: true,
+            show_worktree: true,
+            state_colors: HashMap::new(),
+            show_diff_stats: false,
+            diff_stats_scope: DiffStatsScope::All,
+            show_commit_age: false,
+            show_commit_subject: false,
+            show_commit_author: false,
+            subject_max_length: 30,
+            stale_after_minutes: 0,
+            stale_colors: None,
+            show_upstream: false,
+            base_branch: None,
+            vcs_order: vec![VcsKind::Jujutsu, VcsKind::Git, VcsKind::Mercurial],
+            hyperlink: false,
         }
     }
 }
@@ -152,7 +251,7 @@ impl GitSegmentConfig {
             
         config.sha_length = options.get("sha_length")
             .and_then(|v| v.as_u64())
-            .map(|v| v.max(4).min(40) as u8)
+            .map(|v| v.clamp(4, 40) as u8)
             .unwrap_or(config.sha_length);
             
         config.show_remote = options.get("show_remote")
@@ -173,7 +272,7 @@ impl GitSegmentConfig {
             
         config.branch_max_length = options.get("branch_max_length")
             .and_then(|v| v.as_u64())
-            .map(|v| v.max(5).min(50) as usize)
+            .map(|v| v.clamp(5, 50) as usize)
             .unwrap_or(config.branch_max_length);
             
         if let Some(format_value) = options.get("status_format") {
@@ -181,17 +280,109 @@ impl GitSegmentConfig {
                 config.status_format = format;
             }
         }
+
+        config.show_state = options.get("show_state")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(config.show_state);
+
+        config.show_worktree = options.get("show_worktree")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(config.show_worktree);
+
+        config.status_breakdown = options.get("status_breakdown")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(config.status_breakdown);
+
+        config.state_colors = options.get("state_colors")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or(config.state_colors);
+
+        config.show_diff_stats = options.get("show_diff_stats")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(config.show_diff_stats);
+
+        if let Some(scope_value) = options.get("diff_stats_scope") {
+            if let Ok(scope) = serde_json::from_value::<DiffStatsScope>(scope_value.clone()) {
+                config.diff_stats_scope = scope;
+            }
+        }
+
+        config.show_commit_age = options.get("show_commit_age")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(config.show_commit_age);
+
+        config.show_commit_subject = options.get("show_commit_subject")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(config.show_commit_subject);
+
+        config.show_commit_author = options.get("show_commit_author")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(config.show_commit_author);
+
+        config.subject_max_length = options.get("subject_max_length")
+            .and_then(|v| v.as_u64())
+            .map(|v| v.clamp(5, 100) as usize)
+            .unwrap_or(config.subject_max_length);
+
+        config.stale_after_minutes = options.get("stale_after_minutes")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(config.stale_after_minutes);
+
+        config.stale_colors = options.get("stale_colors")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .or(config.stale_colors);
+
+        config.show_upstream = options.get("show_upstream")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(config.show_upstream);
+
+        config.base_branch = options.get("base_branch")
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .or(config.base_branch);
+
+        config.vcs_order = options.get("vcs_order")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or(config.vcs_order);
+
+        config.hyperlink = options.get("hyperlink")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(config.hyperlink);
         
         config
     }
 }
 
+// Update Segment configuration helper
+#[derive(Debug, Clone, Default)]
+pub struct UpdateSegmentConfig {
+    /// Link the update notice to the release notes (OSC 8)
+    pub hyperlink: bool,
+}
+
+impl UpdateSegmentConfig {
+    pub fn from_options(options: &HashMap<String, serde_json::Value>) -> Self {
+        let mut config = Self::default();
+
+        config.hyperlink = options.get("hyperlink")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(config.hyperlink);
+
+        config
+    }
+}
+
 // Main config structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub style: StyleConfig,
     pub segments: Vec<SegmentConfig>,
     pub theme: String,
+    /// Directories whose project files may add or change segments that run
+    /// commands, plugins or scripts. Only read from the user config.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trusted_projects: Vec<String>,
 }
 
 // Default implementation moved to ui/themes/presets.rs
@@ -200,6 +391,92 @@ pub struct Config {
 pub struct StyleConfig {
     pub mode: StyleMode,
     pub separator: String,
+    /// Colors the terminal can show; `auto` detects from the environment
+    #[serde(default, skip_serializing_if = "ColorMode::is_auto")]
+    pub color_mode: ColorMode,
+    /// Widths of glyphs that depend on the terminal and font
+    #[serde(default, skip_serializing_if = "WidthConfig::is_default")]
+    pub width: WidthConfig,
+    /// Glyphs before the first and after the last segment
+    #[serde(default, skip_serializing_if = "CapsConfig::is_empty")]
+    pub caps: CapsConfig,
+}
+
+/// Start and end caps, drawn in the color of the adjacent segment's background
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CapsConfig {
+    pub start: String,
+    pub end: String,
+}
+
+impl CapsConfig {
+    pub fn is_empty(&self) -> bool {
+        self.start.is_empty() && self.end.is_empty()
+    }
+}
+
+/// Display width, in cells, of characters whose width varies between setups
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WidthConfig {
+    /// East Asian ambiguous-width characters: 1, or 2 for CJK terminals
+    #[serde(deserialize_with = "deserialize_cell_width")]
+    pub ambiguous: u8,
+    /// Private-use glyphs such as Nerd Font icons: 1 for "Mono" fonts, else 2
+    #[serde(deserialize_with = "deserialize_cell_width")]
+    pub private_use: u8,
+}
+
+/// A character occupies one or two terminal cells; anything else is rejected
+fn deserialize_cell_width<'de, D>(deserializer: D) -> Result<u8, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let width = u8::deserialize(deserializer)?;
+    match width {
+        1 | 2 => Ok(width),
+        _ => Err(serde::de::Error::custom(format!(
+            "width must be 1 or 2, got {}",
+            width
+        ))),
+    }
+}
+
+impl Default for WidthConfig {
+    fn default() -> Self {
+        Self {
+            ambiguous: 1,
+            private_use: 1,
+        }
+    }
+}
+
+impl WidthConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ColorMode {
+    #[default]
+    #[serde(rename = "auto")]
+    Auto,
+    #[serde(rename = "truecolor")]
+    TrueColor,
+    #[serde(rename = "256")]
+    Ansi256,
+    #[serde(rename = "16")]
+    Ansi16,
+    #[serde(rename = "none")]
+    None,
+}
+
+impl ColorMode {
+    pub fn is_auto(&self) -> bool {
+        *self == ColorMode::Auto
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -213,13 +490,41 @@ pub enum StyleMode {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SegmentConfig {
     pub id: SegmentId,
+    /// Instance name, required when the same segment type appears more than once
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
     pub enabled: bool,
+    /// Rule that must hold for the segment to be shown, e.g. `cost > 0.5`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub show_when: Option<String>,
+    /// Rule that hides the segment when it holds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hide_when: Option<String>,
     pub icon: IconConfig,
     pub colors: ColorConfig,
     pub styles: TextStyleConfig,
     pub options: HashMap<String, serde_json::Value>,
 }
 
+impl SegmentConfig {
+    /// Unique key of this instance: its name, the plugin name for unnamed
+    /// plugins, or the segment type
+    pub fn key(&self) -> String {
+        if let Some(ref name) = self.name {
+            return name.clone();
+        }
+        if self.id == SegmentId::Plugin {
+            if let Some(plugin) = self.options.get("plugin").and_then(|v| v.as_str()) {
+                return plugin.to_string();
+            }
+        }
+        serde_json::to_value(self.id)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IconConfig {
     pub plain: String,
@@ -233,6 +538,21 @@ pub struct ColorConfig {
     pub background: Option<AnsiColor>,
 }
 
+impl ColorConfig {
+    /// Replace the colors that are set in `other`, keeping the rest
+    pub fn overlay(&mut self, other: &ColorConfig) {
+        if other.icon.is_some() {
+            self.icon = other.icon.clone();
+        }
+        if other.text.is_some() {
+            self.text = other.text.clone();
+        }
+        if other.background.is_some() {
+            self.background = other.background.clone();
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct TextStyleConfig {
     pub text_bold: bool,
@@ -257,6 +577,12 @@ pub enum SegmentId {
     Session,
     OutputStyle,
     Update,
+    Python,
+    Toolchain,
+    Kubernetes,
+    Custom,
+    Plugin,
+    Script,
 }
 
 // Legacy compatibility structure
@@ -269,18 +595,18 @@ pub struct SegmentsConfig {
 }
 
 // Data structures compatible with existing main.rs
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 pub struct Model {
     pub id: String,
     pub display_name: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 pub struct Workspace {
     pub current_dir: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 pub struct Cost {
     pub total_cost_usd: Option<f64>,
     pub total_duration_ms: Option<u64>,
@@ -289,18 +615,41 @@ pub struct Cost {
     pub total_lines_removed: Option<u32>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 pub struct OutputStyle {
     pub name: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 pub struct InputData {
     pub model: Model,
     pub workspace: Workspace,
     pub transcript_path: String,
     pub cost: Option<Cost>,
     pub output_style: Option<OutputStyle>,
+    // Remaining fields sent by Claude Code, kept so they can be forwarded to commands
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl InputData {
+    /// Input for rendering outside Claude Code, such as a tmux status line.
+    /// Only the directory is known, so session segments have nothing to show.
+    pub fn for_directory(current_dir: &str) -> Self {
+        Self {
+            model: Model {
+                id: String::new(),
+                display_name: String::new(),
+            },
+            workspace: Workspace {
+                current_dir: current_dir.to_string(),
+            },
+            transcript_path: String::new(),
+            cost: None,
+            output_style: None,
+            extra: HashMap::new(),
+        }
+    }
 }
 
 // OpenAI-style nested token details
@@ -431,6 +780,7 @@ impl Config {
         // Compare style config
         if self.style.mode != theme_preset.style.mode
             || self.style.separator != theme_preset.style.separator
+            || self.style.caps != theme_preset.style.caps
         {
             return false;
         }
@@ -458,7 +808,10 @@ impl Config {
     /// Compare two segment configs for equality
     fn segment_matches(&self, current: &SegmentConfig, preset: &SegmentConfig) -> bool {
         current.id == preset.id
+            && current.name == preset.name
             && current.enabled == preset.enabled
+            && current.show_when == preset.show_when
+            && current.hide_when == preset.hide_when
             && current.icon.plain == preset.icon.plain
             && current.icon.nerd_font == preset.icon.nerd_font
             && self.color_matches(&current.colors.icon, &preset.colors.icon)
@@ -890,6 +1243,279 @@ impl CostSegmentConfig {
     }
 }
 
+// Python Segment configuration helper
+#[derive(Debug, Clone)]
+pub struct PythonSegmentConfig {
+    pub show_version: bool,
+    pub show_project_version: bool,
+    pub ignore_conda_base: bool,
+}
+
+impl Default for PythonSegmentConfig {
+    fn default() -> Self {
+        Self {
+            show_version: true,
+            show_project_version: true,
+            ignore_conda_base: true,
+        }
+    }
+}
+
+impl PythonSegmentConfig {
+    pub fn from_options(options: &HashMap<String, serde_json::Value>) -> Self {
+        let mut config = Self::default();
+
+        config.show_version = options.get("show_version")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(config.show_version);
+
+        config.show_project_version = options.get("show_project_version")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(config.show_project_version);
+
+        config.ignore_conda_base = options.get("ignore_conda_base")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(config.ignore_conda_base);
+
+        config
+    }
+}
+
+// Toolchain Segment configuration helper
+#[derive(Debug, Clone)]
+pub struct ToolchainSegmentConfig {
+    pub search_ancestors: bool,
+    pub show_version: bool,
+    pub show_language: bool,
+    pub use_language_icon: bool,
+}
+
+impl Default for ToolchainSegmentConfig {
+    fn default() -> Self {
+        Self {
+            search_ancestors: true,
+            show_version: true,
+            show_language: false,
+            use_language_icon: false,
+        }
+    }
+}
+
+impl ToolchainSegmentConfig {
+    pub fn from_options(options: &HashMap<String, serde_json::Value>) -> Self {
+        let mut config = Self::default();
+
+        config.search_ancestors = options.get("search_ancestors")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(config.search_ancestors);
+
+        config.show_version = options.get("show_version")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(config.show_version);
+
+        config.show_language = options.get("show_language")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(config.show_language);
+
+        config.use_language_icon = options.get("use_language_icon")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(config.use_language_icon);
+
+        config
+    }
+}
+
+// Kubernetes Segment configuration helper
+#[derive(Debug, Clone)]
+pub struct KubernetesSegmentConfig {
+    pub show_namespace: bool,
+    pub hide_default_namespace: bool,
+    pub show_aws_profile: bool,
+    pub show_gcloud: bool,
+    pub context_colors: Vec<ContextColorRule>,
+}
+
+/// Colors applied when the current context matches a glob pattern
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextColorRule {
+    pub pattern: String,
+    #[serde(flatten)]
+    pub colors: ColorConfig,
+}
+
+impl Default for KubernetesSegmentConfig {
+    fn default() -> Self {
+        Self {
+            show_namespace: true,
+            hide_default_namespace: false,
+            show_aws_profile: false,
+            show_gcloud: false,
+            context_colors: Vec::new(),
+        }
+    }
+}
+
+impl KubernetesSegmentConfig {
+    pub fn from_options(options: &HashMap<String, serde_json::Value>) -> Self {
+        let mut config = Self::default();
+
+        config.show_namespace = options.get("show_namespace")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(config.show_namespace);
+
+        config.hide_default_namespace = options.get("hide_default_namespace")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(config.hide_default_namespace);
+
+        config.show_aws_profile = options.get("show_aws_profile")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(config.show_aws_profile);
+
+        config.show_gcloud = options.get("show_gcloud")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(config.show_gcloud);
+
+        config.context_colors = options.get("context_colors")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or(config.context_colors);
+
+        config
+    }
+}
+
+// Custom command Segment configuration helper
+#[derive(Debug, Clone)]
+pub struct CustomSegmentConfig {
+    pub command: String,
+    pub shell: Vec<String>,
+    pub timeout_ms: u64,
+    pub cache_ttl: u64,
+    pub max_length: usize,
+}
+
+impl Default for CustomSegmentConfig {
+    fn default() -> Self {
+        let shell = if cfg!(windows) {
+            vec!["cmd".to_string(), "/C".to_string()]
+        } else {
+            vec!["sh".to_string(), "-c".to_string()]
+        };
+
+        Self {
+            command: String::new(),
+            shell,
+            timeout_ms: 500,
+            cache_ttl: 0,
+            max_length: 60,
+        }
+    }
+}
+
+impl CustomSegmentConfig {
+    pub fn from_options(options: &HashMap<String, serde_json::Value>) -> Self {
+        let mut config = Self::default();
+
+        config.command = options.get("command")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .unwrap_or(config.command);
+
+        config.shell = options.get("shell")
+            .and_then(|v| v.as_array())
+            .map(|arr| arr.iter().filter_map(|v| v.as_str()).map(|s| s.to_string()).collect::<Vec<_>>())
+            .filter(|shell| !shell.is_empty())
+            .unwrap_or(config.shell);
+
+        config.timeout_ms = options.get("timeout_ms")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(config.timeout_ms);
+
+        config.cache_ttl = options.get("cache_ttl")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(config.cache_ttl);
+
+        config.max_length = options.get("max_length")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize)
+            .unwrap_or(config.max_length);
+
+        config
+    }
+}
+
+// Plugin Segment configuration helper
+#[derive(Debug, Clone)]
+pub struct PluginSegmentConfig {
+    pub plugin: String,
+    pub timeout_ms: u64,
+}
+
+impl Default for PluginSegmentConfig {
+    fn default() -> Self {
+        Self {
+            plugin: String::new(),
+            timeout_ms: 1000,
+        }
+    }
+}
+
+impl PluginSegmentConfig {
+    pub fn from_options(options: &HashMap<String, serde_json::Value>) -> Self {
+        let mut config = Self::default();
+
+        config.plugin = options.get("plugin")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .unwrap_or(config.plugin);
+
+        config.timeout_ms = options.get("timeout_ms")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(config.timeout_ms);
+
+        config
+    }
+}
+
+// Script Segment configuration helper
+#[derive(Debug, Clone)]
+pub struct ScriptSegmentConfig {
+    pub script: String,
+    pub script_file: Option<String>,
+    pub max_operations: u64,
+}
+
+impl Default for ScriptSegmentConfig {
+    fn default() -> Self {
+        Self {
+            script: String::new(),
+            script_file: None,
+            max_operations: 100_000,
+        }
+    }
+}
+
+impl ScriptSegmentConfig {
+    pub fn from_options(options: &HashMap<String, serde_json::Value>) -> Self {
+        let mut config = Self::default();
+
+        config.script = options.get("script")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .unwrap_or(config.script);
+
+        config.script_file = options.get("script_file")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .or(config.script_file);
+
+        config.max_operations = options.get("max_operations")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(config.max_operations);
+
+        config
+    }
+}
+
 // Legacy alias for backward compatibility
 pub type Usage = RawUsage;
 
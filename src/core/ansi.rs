@@ -0,0 +1,194 @@
+//! Parsing of the generator's ANSI output into styled runs of text, so the
+//! same line can be re-emitted in other markups.
+
+use crate::config::AnsiColor;
+
+/// Graphic rendition in effect for a run of text
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Style {
+    pub fg: Option<AnsiColor>,
+    pub bg: Option<AnsiColor>,
+    pub bold: bool,
+}
+
+impl Style {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Text sharing one style and, optionally, an OSC 8 hyperlink
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyledRun {
+    pub text: String,
+    pub style: Style,
+    pub link: Option<String>,
+}
+
+/// Whether a hyperlink target uses a scheme safe to emit: `http`, `https` or `file`
+pub fn is_allowed_link(url: &str) -> bool {
+    url.split_once(':').is_some_and(|(scheme, _)| {
+        ["http", "https", "file"]
+            .iter()
+            .any(|allowed| scheme.eq_ignore_ascii_case(allowed))
+    })
+}
+
+/// Split ANSI text into styled runs. SGR sequences update the style, OSC 8
+/// sequences the hyperlink; other escape sequences, and links with schemes
+/// outside [`is_allowed_link`], are dropped.
+pub fn parse(ansi: &str) -> Vec<StyledRun> {
+    let mut runs: Vec<StyledRun> = Vec::new();
+    let mut style = Style::default();
+    let mut link: Option<String> = None;
+    let mut text = String::new();
+    let mut chars = ansi.chars().peekable();
+
+    let mut flush = |text: &mut String, style: &Style, link: &Option<String>| {
+        if !text.is_empty() {
+            runs.push(StyledRun {
+                text: std::mem::take(text),
+                style: style.clone(),
+                link: link.clone(),
+            });
+        }
+    };
+
+    while let Some(ch) = chars.next() {
+        if ch != '\x1b' {
+            text.push(ch);
+            continue;
+        }
+
+        match chars.next() {
+            // CSI: parameters up to the final letter; only SGR (`m`) matters
+            Some('[') => {
+                let mut params = String::new();
+                let mut kind = None;
+                for c in chars.by_ref() {
+                    if c.is_alphabetic() {
+                        kind = Some(c);
+                        break;
+                    }
+                    params.push(c);
+                }
+                if kind == Some('m') {
+                    let mut next = style.clone();
+                    apply_sgr(&mut next, &params);
+                    if next != style {
+                        flush(&mut text, &style, &link);
+                        style = next;
+                    }
+                }
+            }
+            // OSC: up to BEL or ESC \
+            Some(']') => {
+                let mut body = String::new();
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' {
+                        if chars.peek() == Some(&'\\') {
+                            chars.next();
+                        }
+                        break;
+                    }
+                    body.push(c);
+                }
+                // OSC 8 ; params ; URI, with an empty URI closing the link
+                if let Some(rest) = body.strip_prefix("8;") {
+                    let url = rest.split_once(';').map(|(_, url)| url).unwrap_or("");
+                    flush(&mut text, &style, &link);
+                    link = is_allowed_link(url).then(|| url.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    flush(&mut text, &style, &link);
+    runs
+}
+
+fn apply_sgr(style: &mut Style, params: &str) {
+    let codes: Vec<u8> = params
+        .split(';')
+        .map(|code| code.parse().unwrap_or(0))
+        .collect();
+    let mut i = 0;
+
+    while i < codes.len() {
+        match codes[i] {
+            0 => *style = Style::default(),
+            1 => style.bold = true,
+            22 => style.bold = false,
+            code @ 30..=37 => style.fg = Some(AnsiColor::Color16 { c16: code - 30 }),
+            code @ 90..=97 => style.fg = Some(AnsiColor::Color16 { c16: code - 82 }),
+            39 => style.fg = None,
+            code @ 40..=47 => style.bg = Some(AnsiColor::Color16 { c16: code - 40 }),
+            code @ 100..=107 => style.bg = Some(AnsiColor::Color16 { c16: code - 92 }),
+            49 => style.bg = None,
+            code @ (38 | 48) => {
+                let color = match codes.get(i + 1) {
+                    Some(5) => {
+                        let color = codes.get(i + 2).map(|&c256| AnsiColor::Color256 { c256 });
+                        i += 2;
+                        color
+                    }
+                    Some(2) => {
+                        let channel = |offset: usize| codes.get(i + offset).copied().unwrap_or(0);
+                        let color = AnsiColor::Rgb {
+                            r: channel(2),
+                            g: channel(3),
+                            b: channel(4),
+                        };
+                        i += 4;
+                        Some(color)
+                    }
+                    _ => None,
+                };
+                if code == 38 {
+                    style.fg = color;
+                } else {
+                    style.bg = color;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sgr_colors_and_hyperlinks() {
+        let runs = parse(
+            "\x1b[48;5;24m \x1b[1;38;2;1;2;3mmain\x1b[0m\x1b]8;;https://x\x1b\\link\x1b]8;;\x1b\\",
+        );
+
+        assert_eq!(runs.len(), 3);
+        assert_eq!(runs[0].text, " ");
+        assert_eq!(runs[0].style.bg, Some(AnsiColor::Color256 { c256: 24 }));
+        assert_eq!(runs[1].text, "main");
+        assert!(runs[1].style.bold);
+        assert_eq!(runs[1].style.fg, Some(AnsiColor::Rgb { r: 1, g: 2, b: 3 }));
+        assert_eq!(runs[2].link.as_deref(), Some("https://x"));
+        assert!(runs[2].style.is_default());
+    }
+
+    #[test]
+    fn drops_links_with_unsafe_schemes() {
+        assert!(is_allowed_link("HTTPS://example.com"));
+        assert!(is_allowed_link("file:///tmp"));
+        assert!(!is_allowed_link("javascript:alert(1)"));
+        assert!(!is_allowed_link("no-scheme"));
+
+        let runs = parse("\x1b]8;;javascript:alert(1)\x1b\\click\x1b]8;;\x1b\\");
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].link, None);
+    }
+}
@@ -0,0 +1,189 @@
+//! Terminal color capability detection and conversion of theme colors
+//! down to what the terminal can show.
+
+use crate::config::{AnsiColor, ColorMode};
+use std::env;
+
+/// Colors the terminal supports, from richest to none
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorSupport {
+    None,
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+impl ColorSupport {
+    /// Resolve the configured mode, detecting from the environment for `auto`.
+    /// An explicit mode wins over `NO_COLOR`.
+    pub fn resolve(mode: ColorMode) -> Self {
+        match mode {
+            ColorMode::TrueColor => ColorSupport::TrueColor,
+            ColorMode::Ansi256 => ColorSupport::Ansi256,
+            ColorMode::Ansi16 => ColorSupport::Ansi16,
+            ColorMode::None => ColorSupport::None,
+            ColorMode::Auto => Self::detect(),
+        }
+    }
+
+    /// Detect from `NO_COLOR`, `COLORTERM`, `TERM_PROGRAM` and `TERM`
+    pub fn detect() -> Self {
+        let var = |name: &str| env::var(name).unwrap_or_default();
+
+        // https://no-color.org: any non-empty value disables color
+        if !var("NO_COLOR").is_empty() {
+            return ColorSupport::None;
+        }
+
+        let colorterm = var("COLORTERM").to_lowercase();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorSupport::TrueColor;
+        }
+
+        // Terminal.app understands 256 colors but not RGB
+        if var("TERM_PROGRAM") == "Apple_Terminal" {
+            return ColorSupport::Ansi256;
+        }
+
+        let term = var("TERM").to_lowercase();
+        if term == "dumb" {
+            ColorSupport::None
+        } else if term.contains("truecolor") || term.contains("24bit") || term.contains("direct") {
+            ColorSupport::TrueColor
+        } else if term.contains("256") {
+            ColorSupport::Ansi256
+        } else if term.is_empty() {
+            // Claude Code may not pass TERM through; keep theme colors as they are
+            ColorSupport::TrueColor
+        } else {
+            ColorSupport::Ansi16
+        }
+    }
+
+    /// Name matching the `color_mode` setting
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColorSupport::None => "none",
+            ColorSupport::Ansi16 => "16",
+            ColorSupport::Ansi256 => "256",
+            ColorSupport::TrueColor => "truecolor",
+        }
+    }
+
+    /// Convert a color to the nearest one this terminal can show
+    pub fn adapt(&self, color: &AnsiColor) -> Option<AnsiColor> {
+        match (self, color) {
+            (ColorSupport::None, _) => None,
+            (ColorSupport::TrueColor, _) => Some(color.clone()),
+            (ColorSupport::Ansi256, AnsiColor::Rgb { r, g, b }) => Some(AnsiColor::Color256 {
+                c256: nearest_256(*r, *g, *b),
+            }),
+            (ColorSupport::Ansi256, _) => Some(color.clone()),
+            (ColorSupport::Ansi16, AnsiColor::Color16 { .. }) => Some(color.clone()),
+            (ColorSupport::Ansi16, AnsiColor::Color256 { c256 }) => {
+                let (r, g, b) = palette_256(*c256);
+                Some(AnsiColor::Color16 {
+                    c16: nearest_16(r, g, b),
+                })
+            }
+            (ColorSupport::Ansi16, AnsiColor::Rgb { r, g, b }) => Some(AnsiColor::Color16 {
+                c16: nearest_16(*r, *g, *b),
+            }),
+        }
+    }
+}
+
+/// RGB value of any color, using xterm's palette for indexed colors
+pub fn to_rgb(color: &AnsiColor) -> (u8, u8, u8) {
+    match color {
+        AnsiColor::Color16 { c16 } => PALETTE_16[(*c16 as usize).min(15)],
+        AnsiColor::Color256 { c256 } => palette_256(*c256),
+        AnsiColor::Rgb { r, g, b } => (*r, *g, *b),
+    }
+}
+
+/// xterm's default 16-color palette
+const PALETTE_16: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// Channel levels of the 6x6x6 color cube (indices 16-231)
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+/// RGB value of a 256-color palette index
+fn palette_256(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => PALETTE_16[index as usize],
+        16..=231 => {
+            let i = index - 16;
+            (
+                CUBE_LEVELS[(i / 36) as usize],
+                CUBE_LEVELS[(i / 6 % 6) as usize],
+                CUBE_LEVELS[(i % 6) as usize],
+            )
+        }
+        _ => {
+            let level = 8 + (index - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+/// Nearest entry in the color cube or grayscale ramp. The first 16 entries are
+/// skipped because terminals commonly redefine them.
+fn nearest_256(r: u8, g: u8, b: u8) -> u8 {
+    (16..=255u8)
+        .min_by_key(|&index| distance(palette_256(index), (r, g, b)))
+        .unwrap_or(16)
+}
+
+fn nearest_16(r: u8, g: u8, b: u8) -> u8 {
+    (0..16u8)
+        .min_by_key(|&index| distance(PALETTE_16[index as usize], (r, g, b)))
+        .unwrap_or(7)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn downsamples_to_nearest_palette_entry() {
+        let rgb = AnsiColor::Rgb { r: 255, g: 0, b: 0 };
+        assert!(matches!(
+            ColorSupport::Ansi256.adapt(&rgb),
+            Some(AnsiColor::Color256 { c256: 196 })
+        ));
+        assert!(matches!(
+            ColorSupport::Ansi16.adapt(&rgb),
+            Some(AnsiColor::Color16 { c16: 9 })
+        ));
+
+        let gray = AnsiColor::Color256 { c256: 244 };
+        assert!(matches!(
+            ColorSupport::Ansi16.adapt(&gray),
+            Some(AnsiColor::Color16 { c16: 8 })
+        ));
+        assert!(ColorSupport::None.adapt(&gray).is_none());
+    }
+}
@@ -0,0 +1,245 @@
+//! SVG and HTML export of a rendered statusline, for docs and theme sharing.
+//! Text is laid out on the terminal's cell grid so Nerd Font glyphs and wide
+//! characters line up as they do in a terminal.
+
+use crate::config::{AnsiColor, WidthConfig};
+use crate::core::ansi::{self, StyledRun};
+use crate::core::color::to_rgb;
+use crate::core::width::display_width;
+
+/// Fonts tried after the user's own, so icons render without a patched font
+const NERD_FONT_FALLBACKS: &[&str] = &[
+    "Symbols Nerd Font Mono",
+    "Symbols Nerd Font",
+    "JetBrainsMono Nerd Font Mono",
+    "Menlo",
+    "Consolas",
+    "monospace",
+];
+
+/// Character cell width relative to the font size, typical for monospace fonts
+const CELL_WIDTH: f32 = 0.6;
+const LINE_HEIGHT: f32 = 1.5;
+
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    /// Fonts tried before the Nerd Font fallbacks
+    pub fonts: Vec<String>,
+    pub font_size: f32,
+    /// Terminal default colors, used where the theme sets none
+    pub foreground: (u8, u8, u8),
+    pub background: (u8, u8, u8),
+    pub widths: WidthConfig,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            fonts: Vec::new(),
+            font_size: 14.0,
+            foreground: (0xd4, 0xd4, 0xd4),
+            background: (0x1e, 0x1e, 0x1e),
+            widths: WidthConfig::default(),
+        }
+    }
+}
+
+impl ExportOptions {
+    fn font_family(&self) -> String {
+        self.fonts
+            .iter()
+            .map(String::as_str)
+            .chain(NERD_FONT_FALLBACKS.iter().copied())
+            .map(|font| {
+                if font == "monospace" {
+                    font.to_string()
+                } else {
+                    format!("'{}'", font.replace('\'', ""))
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn hex(&self, color: Option<&AnsiColor>, default: (u8, u8, u8)) -> String {
+        let (r, g, b) = color.map(to_rgb).unwrap_or(default);
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Render ANSI lines as an SVG image
+pub fn to_svg(lines: &[String], options: &ExportOptions) -> String {
+    let cell = options.font_size * CELL_WIDTH;
+    let line_height = options.font_size * LINE_HEIGHT;
+    let padding = cell;
+
+    let rows: Vec<Vec<StyledRun>> = lines.iter().map(|line| ansi::parse(line)).collect();
+    let columns = rows
+        .iter()
+        .map(|runs| {
+            runs.iter()
+                .map(|run| display_width(&run.text, &options.widths))
+                .sum::<usize>()
+        })
+        .max()
+        .unwrap_or(0);
+    let width = columns as f32 * cell + 2.0 * padding;
+    let height = rows.len().max(1) as f32 * line_height + 2.0 * padding;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w:.0}\" height=\"{h:.0}\" viewBox=\"0 0 {w:.1} {h:.1}\">\n",
+        w = width,
+        h = height
+    );
+    svg.push_str(&format!(
+        "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>\n",
+        options.hex(None, options.background)
+    ));
+    svg.push_str(&format!(
+        "<g font-family=\"{}\" font-size=\"{}\" xml:space=\"preserve\">\n",
+        escape_xml(&options.font_family()),
+        options.font_size
+    ));
+
+    for (row, runs) in rows.iter().enumerate() {
+        let top = padding + row as f32 * line_height;
+        // Baseline roughly centered in the line for common monospace metrics
+        let baseline = top + line_height * 0.72;
+        let mut column = 0;
+
+        for run in runs {
+            let cells = display_width(&run.text, &options.widths);
+            let x = padding + column as f32 * cell;
+            let run_width = cells as f32 * cell;
+            column += cells;
+
+            if let Some(bg) = &run.style.bg {
+                // Slight overlap hides hairline seams between adjacent cells
+                svg.push_str(&format!(
+                    "<rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\" fill=\"{}\"/>\n",
+                    x,
+                    top,
+                    run_width + 0.5,
+                    line_height,
+                    options.hex(Some(bg), options.background)
+                ));
+            }
+            if run.text.trim().is_empty() {
+                continue;
+            }
+
+            // textLength pins the run to its cells whatever the fallback font's advance
+            let text = format!(
+                "<text x=\"{:.2}\" y=\"{:.2}\" fill=\"{}\"{} textLength=\"{:.2}\" lengthAdjust=\"spacingAndGlyphs\">{}</text>",
+                x,
+                baseline,
+                options.hex(run.style.fg.as_ref(), options.foreground),
+                if run.style.bold { " font-weight=\"bold\"" } else { "" },
+                run_width,
+                escape_xml(&run.text)
+            );
+            match &run.link {
+                Some(url) => {
+                    svg.push_str(&format!("<a href=\"{}\">{}</a>\n", escape_xml(url), text))
+                }
+                None => {
+                    svg.push_str(&text);
+                    svg.push('\n');
+                }
+            }
+        }
+    }
+
+    svg.push_str("</g>\n</svg>\n");
+    svg
+}
+
+/// Render ANSI lines as a standalone HTML page
+pub fn to_html(lines: &[String], options: &ExportOptions) -> String {
+    let mut body = String::new();
+
+    for (row, line) in lines.iter().enumerate() {
+        if row > 0 {
+            body.push('\n');
+        }
+        for run in ansi::parse(line) {
+            let mut style = String::new();
+            if let Some(fg) = &run.style.fg {
+                style.push_str(&format!(
+                    "color:{};",
+                    options.hex(Some(fg), options.foreground)
+                ));
+            }
+            if let Some(bg) = &run.style.bg {
+                style.push_str(&format!(
+                    "background:{};",
+                    options.hex(Some(bg), options.background)
+                ));
+            }
+            if run.style.bold {
+                style.push_str("font-weight:bold;");
+            }
+
+            let mut text = escape_xml(&run.text);
+            if !style.is_empty() {
+                text = format!("<span style=\"{}\">{}</span>", style, text);
+            }
+            if let Some(url) = &run.link {
+                text = format!("<a href=\"{}\">{}</a>", escape_xml(url), text);
+            }
+            body.push_str(&text);
+        }
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>ccline</title>
+<style>
+pre.ccline {{ display: inline-block; margin: 0; padding: 0.6em; background: {background}; color: {foreground}; font-family: {fonts}; font-size: {size}px; line-height: {line_height}; }}
+pre.ccline a {{ color: inherit; text-decoration: none; }}
+</style>
+</head>
+<body>
+<pre class="ccline">{body}</pre>
+</body>
+</html>
+"#,
+        background = options.hex(None, options.background),
+        foreground = options.hex(None, options.foreground),
+        fonts = options.font_family(),
+        size = options.font_size,
+        line_height = LINE_HEIGHT,
+        body = body
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn svg_places_runs_on_the_cell_grid() {
+        let options = ExportOptions::default();
+        let svg = to_svg(
+            &["\x1b[48;2;1;2;3m\x1b[38;5;196m项目 \x1b[0m<a>".to_string()],
+            &options,
+        );
+
+        // Five cells for the CJK text and space, three for "<a>", plus padding
+        assert!(svg.contains("width=\"84\""));
+        assert!(svg.contains("fill=\"#010203\""));
+        assert!(svg.contains("fill=\"#ff0000\""));
+        assert!(svg.contains(">&lt;a&gt;</text>"));
+        assert!(svg.contains("x=\"50.40\""));
+    }
+}
@@ -0,0 +1,43 @@
+//! Versioned JSON schema for `--output-format json`, for scripts and tools
+//! that wrap ccline. Fields are only added within a version; renaming or
+//! removing one bumps [`SCHEMA_VERSION`].
+
+use crate::config::{ColorConfig, SegmentId};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize)]
+pub struct StatusLineJson {
+    pub version: u32,
+    /// Colors the line was rendered for: "truecolor", "256", "16" or "none"
+    pub color_support: &'static str,
+    /// The full line as printed in ANSI mode
+    pub ansi: String,
+    /// The full line without styling
+    pub plain: String,
+    /// Time spent collecting all segments, in microseconds
+    pub elapsed_us: u64,
+    pub segments: Vec<SegmentJson>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SegmentJson {
+    pub id: SegmentId,
+    /// Instance key: the name, the plugin name, or the segment type
+    pub key: String,
+    pub name: Option<String>,
+    pub icon: String,
+    pub primary: String,
+    pub secondary: String,
+    /// Everything the segment reported, sorted by key
+    pub metadata: BTreeMap<String, String>,
+    /// Colors after state overrides and conversion for the terminal
+    pub colors: ColorConfig,
+    pub bold: bool,
+    /// The segment as rendered, without separators
+    pub ansi: String,
+    /// Time spent collecting this segment, in microseconds
+    pub elapsed_us: u64,
+}
@@ -1,4 +1,14 @@
+pub mod ansi;
+pub mod color;
+pub mod export;
+pub mod json;
+pub mod output;
+pub mod powerline;
+pub mod samples;
 pub mod segments;
 pub mod statusline;
+pub mod width;
 
-pub use statusline::{collect_all_segments, StatusLineGenerator};
+pub use statusline::{
+    collect_all_segments, collect_all_segments_timed, StatusLineGenerator, TimedSegment,
+};
@@ -0,0 +1,162 @@
+//! Output targets besides the ANSI statusline: tmux status markup, zsh and
+//! bash prompt escapes, and plain text. Each converts the generator's ANSI
+//! line, so every target renders the same segments and theme.
+
+use crate::config::AnsiColor;
+use crate::core::ansi::{self, Style};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// ANSI escape sequences, for Claude Code and terminals
+    #[default]
+    Ansi,
+    /// tmux `#[fg=…,bg=…]` markup, for status-left/status-right
+    Tmux,
+    /// zsh `%F{…}`/`%K{…}` prompt escapes
+    Zsh,
+    /// ANSI escapes wrapped in `\[ \]` for bash's PS1
+    Bash,
+    /// Text without any styling
+    Plain,
+    /// Collected segments as versioned JSON, see [`crate::core::json`]
+    Json,
+}
+
+impl OutputFormat {
+    /// Convert an ANSI line to this format
+    pub fn convert(&self, line: &str) -> String {
+        // JSON is built from the segments by `StatusLineGenerator::generate_json`
+        if matches!(self, OutputFormat::Ansi | OutputFormat::Json) {
+            return line.to_string();
+        }
+
+        let mut output = String::new();
+        let mut current = Style::default();
+
+        for run in ansi::parse(line) {
+            if run.style != current {
+                output.push_str(&self.style_markup(&run.style));
+                current = run.style;
+            }
+            output.push_str(&self.escape(&run.text));
+        }
+
+        if !current.is_default() {
+            output.push_str(&self.style_markup(&Style::default()));
+        }
+        output
+    }
+
+    /// Markup switching to `style` from whatever was in effect
+    fn style_markup(&self, style: &Style) -> String {
+        match self {
+            OutputFormat::Ansi | OutputFormat::Plain | OutputFormat::Json => String::new(),
+            OutputFormat::Tmux => {
+                if style.is_default() {
+                    return "#[default]".to_string();
+                }
+                let color = |color: &Option<AnsiColor>| match color {
+                    Some(AnsiColor::Color16 { c16 }) => format!("colour{}", c16),
+                    Some(AnsiColor::Color256 { c256 }) => format!("colour{}", c256),
+                    Some(AnsiColor::Rgb { r, g, b }) => format!("#{:02x}{:02x}{:02x}", r, g, b),
+                    None => "default".to_string(),
+                };
+                format!(
+                    "#[fg={},bg={},{}]",
+                    color(&style.fg),
+                    color(&style.bg),
+                    if style.bold { "bold" } else { "nobold" }
+                )
+            }
+            OutputFormat::Zsh => {
+                let color = |color: &Option<AnsiColor>, start: char, end: char| match color {
+                    Some(AnsiColor::Color16 { c16 }) => format!("%{}{{{}}}", start, c16),
+                    Some(AnsiColor::Color256 { c256 }) => format!("%{}{{{}}}", start, c256),
+                    Some(AnsiColor::Rgb { r, g, b }) => {
+                        format!("%{}{{#{:02x}{:02x}{:02x}}}", start, r, g, b)
+                    }
+                    None => format!("%{}", end),
+                };
+                format!(
+                    "{}{}{}",
+                    if style.bold { "%B" } else { "%b" },
+                    color(&style.fg, 'F', 'f'),
+                    color(&style.bg, 'K', 'k')
+                )
+            }
+            OutputFormat::Bash => {
+                let mut codes = vec!["0".to_string()];
+                if style.bold {
+                    codes.push("1".to_string());
+                }
+                for (color, base, bright, extended) in
+                    [(&style.fg, 30, 90, 38), (&style.bg, 40, 100, 48)]
+                {
+                    match color {
+                        Some(AnsiColor::Color16 { c16 }) if *c16 < 8 => {
+                            codes.push((base + *c16 as u16).to_string())
+                        }
+                        Some(AnsiColor::Color16 { c16 }) => {
+                            codes.push((bright + *c16 as u16 - 8).to_string())
+                        }
+                        Some(AnsiColor::Color256 { c256 }) => {
+                            codes.push(format!("{};5;{}", extended, c256))
+                        }
+                        Some(AnsiColor::Rgb { r, g, b }) => {
+                            codes.push(format!("{};2;{};{};{}", extended, r, g, b))
+                        }
+                        None => {}
+                    }
+                }
+                format!("\\[\\e[{}m\\]", codes.join(";"))
+            }
+        }
+    }
+
+    /// Escape characters the target would otherwise interpret
+    fn escape(&self, text: &str) -> String {
+        match self {
+            OutputFormat::Tmux => text.replace('#', "##"),
+            OutputFormat::Zsh => text.replace('%', "%%"),
+            // PS1 is decoded (`\\` to `\`) and then expanded like a double-quoted
+            // string, so `\`, `$` and `` ` `` need a backslash left after decoding
+            OutputFormat::Bash => text
+                .replace('\\', "\\\\\\\\")
+                .replace('$', "\\\\$")
+                .replace('`', "\\\\`"),
+            OutputFormat::Ansi | OutputFormat::Plain | OutputFormat::Json => text.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_ansi_to_each_target() {
+        let line = "\x1b[1;38;5;111m#1 100%\x1b[0m | \x1b[44mx\x1b[49m";
+
+        assert_eq!(
+            OutputFormat::Tmux.convert(line),
+            "#[fg=colour111,bg=default,bold]##1 100%#[default] | #[fg=default,bg=colour4,nobold]x#[default]"
+        );
+        assert_eq!(
+            OutputFormat::Zsh.convert(line),
+            "%B%F{111}%k#1 100%%%b%f%k | %b%f%K{4}x%b%f%k"
+        );
+        assert_eq!(
+            OutputFormat::Bash.convert(line),
+            "\\[\\e[0;1;38;5;111m\\]#1 100%\\[\\e[0m\\] | \\[\\e[0;44m\\]x\\[\\e[0m\\]"
+        );
+        assert_eq!(OutputFormat::Plain.convert(line), "#1 100% | x");
+    }
+
+    #[test]
+    fn bash_escapes_expansions_in_segment_text() {
+        assert_eq!(
+            OutputFormat::Bash.convert("$(touch x) `id` a\\b"),
+            "\\\\$(touch x) \\\\`id\\\\` a\\\\\\\\b"
+        );
+    }
+}
@@ -0,0 +1,62 @@
+//! Powerline separator glyphs. Each family has a solid separator drawn
+//! between segments of different colors, a thin one for segments sharing a
+//! background, and a left-facing counterpart that works as a start cap.
+
+/// One family of Powerline separator glyphs
+#[derive(Debug, Clone, Copy)]
+pub struct PowerlineFamily {
+    pub name: &'static str,
+    /// Drawn in the previous segment's background over the next one's
+    pub solid: &'static str,
+    /// Drawn between segments with the same background. Families without a
+    /// thin glyph merge such segments seamlessly.
+    pub thin: Option<&'static str>,
+    /// Mirror of `solid`, for the start of the line
+    pub start_cap: &'static str,
+}
+
+pub const POWERLINE_FAMILIES: &[PowerlineFamily] = &[
+    PowerlineFamily {
+        name: "Arrow",
+        solid: "\u{e0b0}",
+        thin: Some("\u{e0b1}"),
+        start_cap: "\u{e0b2}",
+    },
+    PowerlineFamily {
+        name: "Rounded",
+        solid: "\u{e0b4}",
+        thin: Some("\u{e0b5}"),
+        start_cap: "\u{e0b6}",
+    },
+    PowerlineFamily {
+        name: "Slanted",
+        solid: "\u{e0bc}",
+        thin: Some("\u{e0bd}"),
+        start_cap: "\u{e0ba}",
+    },
+    PowerlineFamily {
+        name: "Backslant",
+        solid: "\u{e0b8}",
+        thin: Some("\u{e0b9}"),
+        start_cap: "\u{e0be}",
+    },
+    PowerlineFamily {
+        name: "Flame",
+        solid: "\u{e0c0}",
+        thin: Some("\u{e0c1}"),
+        start_cap: "\u{e0c2}",
+    },
+    PowerlineFamily {
+        name: "Pixelated",
+        solid: "\u{e0c4}",
+        thin: None,
+        start_cap: "\u{e0c5}",
+    },
+];
+
+/// The family whose solid glyph is the configured separator
+pub fn family_for(separator: &str) -> Option<&'static PowerlineFamily> {
+    POWERLINE_FAMILIES
+        .iter()
+        .find(|family| family.solid == separator)
+}
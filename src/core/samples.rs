@@ -0,0 +1,507 @@
+//! Mock segment data for the TUI preview and for `ccline render --sample`,
+//! plus replaying recorded sessions frame by frame.
+
+use crate::config::{Config, InputData, SegmentConfig, SegmentId};
+use crate::core::segments::{SegmentData, SegmentRegistry};
+use crate::core::statusline::is_segment_visible;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Representative data for a segment, independent of the environment
+pub fn mock_segment_data(segment_config: &SegmentConfig) -> SegmentData {
+    match segment_config.id {
+        SegmentId::Model => SegmentData {
+            primary: "Sonnet 4".to_string(),
+            secondary: "".to_string(),
+            metadata: {
+                let mut map = HashMap::new();
+                map.insert("model".to_string(), "claude-4-sonnet-20250512".to_string());
+                map
+            },
+        },
+        SegmentId::Directory => SegmentData {
+            primary: "CCometixLine".to_string(),
+            secondary: "".to_string(),
+            metadata: {
+                let mut map = HashMap::new();
+                map.insert("current_dir".to_string(), "~/CCometixLine".to_string());
+                map
+            },
+        },
+        SegmentId::Git => SegmentData {
+            primary: "master".to_string(),
+            secondary: "✓".to_string(),
+            metadata: {
+                let mut map = HashMap::new();
+                map.insert("branch".to_string(), "master".to_string());
+                map.insert("status".to_string(), "Clean".to_string());
+                map.insert("ahead".to_string(), "0".to_string());
+                map.insert("behind".to_string(), "0".to_string());
+                map
+            },
+        },
+        SegmentId::Usage => SegmentData {
+            primary: "78.2%".to_string(),
+            secondary: "· 156.4k".to_string(),
+            metadata: {
+                let mut map = HashMap::new();
+                map.insert("total_tokens".to_string(), "156400".to_string());
+                map.insert("percentage".to_string(), "78.2".to_string());
+                map.insert("session_tokens".to_string(), "48200".to_string());
+                map
+            },
+        },
+        SegmentId::Cost => SegmentData {
+            primary: "$0.02".to_string(),
+            secondary: "".to_string(),
+            metadata: {
+                let mut map = HashMap::new();
+                map.insert("cost".to_string(), "0.01234".to_string());
+                map
+            },
+        },
+        SegmentId::Session => SegmentData {
+            primary: "3m45s".to_string(),
+            secondary: "+156 -23".to_string(),
+            metadata: {
+                let mut map = HashMap::new();
+                map.insert("duration_ms".to_string(), "225000".to_string());
+                map.insert("lines_added".to_string(), "156".to_string());
+                map.insert("lines_removed".to_string(), "23".to_string());
+                map
+            },
+        },
+        SegmentId::OutputStyle => SegmentData {
+            primary: "default".to_string(),
+            secondary: "".to_string(),
+            metadata: {
+                let mut map = HashMap::new();
+                map.insert("style_name".to_string(), "default".to_string());
+                map
+            },
+        },
+        SegmentId::Update => SegmentData {
+            primary: format!("v{}", env!("CARGO_PKG_VERSION")),
+            secondary: "".to_string(),
+            metadata: {
+                let mut map = HashMap::new();
+                map.insert(
+                    "current_version".to_string(),
+                    env!("CARGO_PKG_VERSION").to_string(),
+                );
+                map.insert("update_available".to_string(), "false".to_string());
+                map
+            },
+        },
+        SegmentId::Python => SegmentData {
+            primary: "CCometixLine".to_string(),
+            secondary: "3.12.4".to_string(),
+            metadata: {
+                let mut map = HashMap::new();
+                map.insert("env_type".to_string(), "venv".to_string());
+                map.insert("env_name".to_string(), "CCometixLine".to_string());
+                map.insert("version".to_string(), "3.12.4".to_string());
+                map
+            },
+        },
+        SegmentId::Toolchain => SegmentData {
+            primary: "1.89.0".to_string(),
+            secondary: "".to_string(),
+            metadata: {
+                let mut map = HashMap::new();
+                map.insert("language".to_string(), "rust".to_string());
+                map.insert("version".to_string(), "1.89.0".to_string());
+                map
+            },
+        },
+        SegmentId::Kubernetes => SegmentData {
+            primary: "staging-eu".to_string(),
+            secondary: "payments".to_string(),
+            metadata: {
+                let mut map = HashMap::new();
+                map.insert("context".to_string(), "staging-eu".to_string());
+                map.insert("namespace".to_string(), "payments".to_string());
+                map
+            },
+        },
+        SegmentId::Custom => SegmentData {
+            primary: "PROJ-1234".to_string(),
+            secondary: "".to_string(),
+            metadata: HashMap::new(),
+        },
+        SegmentId::Script => SegmentData {
+            primary: "script".to_string(),
+            secondary: "".to_string(),
+            metadata: HashMap::new(),
+        },
+        SegmentId::Plugin => SegmentData {
+            primary: segment_config
+                .options
+                .get("plugin")
+                .and_then(|v| v.as_str())
+                .unwrap_or("plugin")
+                .to_string(),
+            secondary: "".to_string(),
+            metadata: HashMap::new(),
+        },
+    }
+}
+
+/// Mock data for every enabled segment, as shown in the TUI preview
+pub fn preview_segments(config: &Config) -> Vec<(SegmentConfig, SegmentData)> {
+    config
+        .segments
+        .iter()
+        .filter(|segment_config| segment_config.enabled)
+        .map(|segment_config| (segment_config.clone(), mock_segment_data(segment_config)))
+        .collect()
+}
+
+/// Built-in sessions for `ccline render --sample`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Sample {
+    /// Three hours of work: context filling up, cost and changes growing
+    LongSession,
+    /// A detached HEAD with uncommitted changes
+    DetachedHead,
+}
+
+/// One point of the long session: minutes in, context %, cost, lines added/removed
+const LONG_SESSION: [(u64, f64, f64, u32, u32); 6] = [
+    (2, 8.5, 0.04, 12, 0),
+    (15, 27.3, 0.61, 148, 21),
+    (40, 49.8, 1.87, 402, 96),
+    (75, 71.2, 3.92, 855, 240),
+    (120, 88.6, 6.45, 1310, 415),
+    (180, 97.4, 9.80, 1894, 602),
+];
+
+fn data(primary: String, secondary: String, metadata: &[(&str, String)]) -> SegmentData {
+    SegmentData {
+        primary,
+        secondary,
+        metadata: metadata
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.clone()))
+            .collect(),
+    }
+}
+
+fn format_duration(minutes: u64) -> String {
+    if minutes >= 60 {
+        format!("{}h{}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m0s", minutes)
+    }
+}
+
+impl Sample {
+    /// The sample's frames, oldest first, each with the segments' state colors
+    /// and visibility rules applied as for collected data
+    pub fn frames(&self, config: &Config) -> Vec<Vec<(SegmentConfig, SegmentData)>> {
+        let frames: Vec<Vec<(SegmentConfig, SegmentData)>> = match self {
+            Sample::LongSession => LONG_SESSION
+                .iter()
+                .map(|&(minutes, percentage, cost, added, removed)| {
+                    preview_segments(config)
+                        .into_iter()
+                        .map(|(segment_config, mock)| {
+                            let tokens = (percentage * 2000.0) as u32;
+                            let data = match segment_config.id {
+                                SegmentId::Usage => data(
+                                    format!("{:.1}%", percentage),
+                                    format!("· {:.1}k", tokens as f64 / 1000.0),
+                                    &[
+                                        ("total_tokens", tokens.to_string()),
+                                        ("percentage", percentage.to_string()),
+                                    ],
+                                ),
+                                SegmentId::Cost => data(
+                                    format!("${:.2}", cost),
+                                    String::new(),
+                                    &[("cost", cost.to_string())],
+                                ),
+                                SegmentId::Session => data(
+                                    format_duration(minutes),
+                                    format!("+{} -{}", added, removed),
+                                    &[
+                                        ("duration_ms", (minutes * 60_000).to_string()),
+                                        ("lines_added", added.to_string()),
+                                        ("lines_removed", removed.to_string()),
+                                    ],
+                                ),
+                                SegmentId::Git => data(
+                                    "master".to_string(),
+                                    format!("● ~{}", added / 100 + 1),
+                                    &[
+                                        ("branch", "master".to_string()),
+                                        ("status", "Dirty".to_string()),
+                                        ("modified", (added / 100 + 1).to_string()),
+                                    ],
+                                ),
+                                _ => mock,
+                            };
+                            (segment_config, data)
+                        })
+                        .collect()
+                })
+                .collect(),
+            Sample::DetachedHead => vec![preview_segments(config)
+                .into_iter()
+                .map(|(segment_config, mock)| {
+                    let data = match segment_config.id {
+                        SegmentId::Git => data(
+                            "detached".to_string(),
+                            "● ~2".to_string(),
+                            &[
+                                ("branch", "detached".to_string()),
+                                ("status", "Dirty".to_string()),
+                                ("sha", "3f9c2ab".to_string()),
+                                ("modified", "2".to_string()),
+                            ],
+                        ),
+                        _ => mock,
+                    };
+                    (segment_config, data)
+                })
+                .collect()],
+        };
+
+        let registry = SegmentRegistry::new();
+        frames
+            .into_iter()
+            .map(|frame| {
+                frame
+                    .into_iter()
+                    .map(|(mut segment_config, data)| {
+                        registry
+                            .create(&segment_config)
+                            .resolve_style(&mut segment_config, &data);
+                        (segment_config, data)
+                    })
+                    .filter(|(segment_config, data)| is_segment_visible(segment_config, data))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Statusline inputs from `--input` or stdin: one JSON document, or several
+/// in a row (one per line in a recorded session)
+pub fn parse_inputs(raw: &str) -> Result<Vec<InputData>, serde_json::Error> {
+    serde_json::Deserializer::from_str(raw)
+        .into_iter::<InputData>()
+        .collect()
+}
+
+/// Removes the scratch transcript however the replay ends
+struct ScratchFile<'a>(&'a Path);
+
+impl Drop for ScratchFile<'_> {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(self.0);
+    }
+}
+
+/// Create a new scratch file in `dir`. `create_new` never opens an existing
+/// path, so a file or symlink planted in a shared temp dir is skipped.
+fn create_scratch_file(dir: &Path) -> std::io::Result<(std::fs::File, PathBuf)> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut attempt = 0;
+    loop {
+        let path = dir.join(format!(
+            "ccline-render-{}-{}.jsonl",
+            std::process::id(),
+            attempt
+        ));
+        match options.open(&path) {
+            Ok(file) => return Ok((file, path)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists && attempt < 100 => {
+                attempt += 1
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Replay a Claude Code transcript, calling `frame` with the statusline input
+/// after each assistant response. The input points at a scratch file created
+/// in `scratch_dir`, which holds the transcript up to that response and grows
+/// frame by frame; it is removed afterwards. Only the final state is replayed
+/// unless `all_frames` is set.
+pub fn transcript_inputs<T>(
+    path: &Path,
+    scratch_dir: &Path,
+    all_frames: bool,
+    mut frame: impl FnMut(&InputData) -> T,
+) -> Result<Vec<T>, Box<dyn std::error::Error>> {
+    let content = std::fs::read_to_string(path)?;
+    let lines: Vec<&str> = content.lines().collect();
+
+    // Claude Code records the working directory and model on each entry
+    let mut model = String::new();
+    let mut cwd = String::new();
+    let mut responses = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        let Ok(entry) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        if let Some(dir) = entry.get("cwd").and_then(|dir| dir.as_str()) {
+            cwd = dir.to_string();
+        }
+        if entry.get("type").and_then(|kind| kind.as_str()) == Some("assistant") {
+            if let Some(id) = entry.pointer("/message/model").and_then(|id| id.as_str()) {
+                model = id.to_string();
+            }
+            responses.push((index, model.clone(), cwd.clone()));
+        }
+    }
+
+    if cwd.is_empty() {
+        cwd = std::env::current_dir()?.to_string_lossy().into_owned();
+    }
+    let input = |model: &str, cwd: &str, transcript: &Path| {
+        let mut input = InputData::for_directory(cwd);
+        input.model.id = model.to_string();
+        input.model.display_name = model.to_string();
+        input.transcript_path = transcript.to_string_lossy().into_owned();
+        input
+    };
+
+    if !all_frames || responses.is_empty() {
+        return Ok(vec![frame(&input(&model, &cwd, path))]);
+    }
+
+    let (mut file, scratch) = create_scratch_file(scratch_dir)?;
+    let _cleanup = ScratchFile(&scratch);
+    let mut written = 0;
+    let mut frames = Vec::with_capacity(responses.len());
+    for (index, model, cwd) in &responses {
+        for line in &lines[written..=*index] {
+            writeln!(file, "{}", line)?;
+        }
+        written = index + 1;
+        frames.push(frame(&input(model, cwd, &scratch)));
+    }
+    Ok(frames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_dir::TestDir;
+
+    #[test]
+    fn long_session_frames_apply_visibility_rules() {
+        let mut config = Config::default();
+        let usage = config
+            .segments
+            .iter_mut()
+            .find(|segment| segment.id == SegmentId::Usage)
+            .unwrap();
+        usage.enabled = true;
+        usage.show_when = Some("percentage > 80".to_string());
+
+        let frames = Sample::LongSession.frames(&config);
+        let shows_usage: Vec<bool> = frames
+            .iter()
+            .map(|frame| {
+                frame
+                    .iter()
+                    .any(|(segment, _)| segment.id == SegmentId::Usage)
+            })
+            .collect();
+
+        assert_eq!(shows_usage, [false, false, false, false, true, true]);
+    }
+
+    #[test]
+    fn parses_one_or_several_input_documents() {
+        let one = parse_inputs(r#"{"model": {"id": "a", "display_name": "A"}, "workspace": {"current_dir": "/a"}, "transcript_path": ""}"#).unwrap();
+        assert_eq!(one.len(), 1);
+
+        let several = parse_inputs(
+            r#"{"model": {"id": "a", "display_name": "A"}, "workspace": {"current_dir": "/a"}, "transcript_path": ""}
+{"model": {"id": "b", "display_name": "B"},
+ "workspace": {"current_dir": "/b"}, "transcript_path": ""}"#,
+        )
+        .unwrap();
+        let ids: Vec<_> = several
+            .iter()
+            .map(|input| input.model.id.as_str())
+            .collect();
+        assert_eq!(ids, ["a", "b"]);
+
+        assert!(parse_inputs(r#"{"model": {"id": "a"}} {"#).is_err());
+    }
+
+    #[test]
+    fn replays_transcript_one_frame_per_response() {
+        let dir = TestDir::new("transcript");
+        let transcript = dir.join("session.jsonl");
+        let scratch = dir.join("scratch");
+        std::fs::create_dir(&scratch).unwrap();
+        // A planted link at the first scratch name must not be followed
+        let victim = dir.join("victim");
+        std::fs::write(&victim, "keep").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(
+            &victim,
+            scratch.join(format!("ccline-render-{}-0.jsonl", std::process::id())),
+        )
+        .unwrap();
+        std::fs::write(
+            &transcript,
+            [
+                r#"{"type": "user", "cwd": "/work"}"#,
+                r#"{"type": "assistant", "message": {"model": "claude-a"}}"#,
+                r#"{"type": "user"}"#,
+                r#"{"type": "assistant", "message": {"model": "claude-b"}}"#,
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+
+        let frames = transcript_inputs(&transcript, &scratch, true, |input| {
+            let lines = std::fs::read_to_string(&input.transcript_path)
+                .unwrap()
+                .lines()
+                .count();
+            (
+                input.model.id.clone(),
+                input.workspace.current_dir.clone(),
+                lines,
+            )
+        });
+        let scratch_left = std::fs::read_dir(&scratch)
+            .unwrap()
+            .flatten()
+            .filter(|entry| !entry.path().is_symlink())
+            .count();
+        let last = transcript_inputs(&transcript, &scratch, false, |input| {
+            (input.model.id.clone(), input.transcript_path.clone())
+        });
+
+        assert_eq!(
+            frames.unwrap(),
+            [
+                ("claude-a".to_string(), "/work".to_string(), 2),
+                ("claude-b".to_string(), "/work".to_string(), 4),
+            ]
+        );
+        assert_eq!(scratch_left, 0);
+        assert_eq!(std::fs::read_to_string(&victim).unwrap(), "keep");
+        assert_eq!(
+            last.unwrap(),
+            [(
+                "claude-b".to_string(),
+                transcript.to_string_lossy().into_owned()
+            )]
+        );
+    }
+}
@@ -26,8 +26,6 @@ impl CostSegment {
                     "$0".to_string()
                 } else if cost < 0.01 {
                     format!("${:.3}", cost)
-                } else if cost < 1.0 {
-                    format!("${:.2}", cost)
                 } else {
                     format!("${:.2}", cost)
                 }
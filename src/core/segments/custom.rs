@@ -0,0 +1,234 @@
+use super::process::run_with_timeout;
+use super::{Segment, SegmentData};
+use crate::config::{CustomSegmentConfig, InputData, SegmentId};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Structured output a command may print instead of plain text
+#[derive(Debug, Deserialize)]
+struct CommandOutput {
+    primary: String,
+    #[serde(default)]
+    secondary: String,
+    #[serde(default)]
+    metadata: HashMap<String, serde_json::Value>,
+}
+
+/// Cached result of a previous command run
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    timestamp: u64,
+    primary: String,
+    secondary: String,
+    metadata: HashMap<String, String>,
+}
+
+pub struct CustomSegment {
+    config: CustomSegmentConfig,
+}
+
+impl CustomSegment {
+    pub fn with_config(options: &HashMap<String, serde_json::Value>) -> Self {
+        Self {
+            config: CustomSegmentConfig::from_options(options),
+        }
+    }
+
+    /// Run the configured command through the shell, feeding the input JSON on stdin
+    fn run_command(&self, input_json: &str, working_dir: &str) -> Option<String> {
+        let (program, args) = self.config.shell.split_first()?;
+
+        let mut command = Command::new(program);
+        command.args(args).arg(&self.config.command);
+        if !working_dir.is_empty() {
+            command.current_dir(working_dir);
+        }
+
+        run_with_timeout(
+            command,
+            input_json,
+            Duration::from_millis(self.config.timeout_ms),
+        )
+    }
+
+    /// Turn command output into segment data, accepting JSON or plain text
+    fn parse_output(&self, output: &str) -> Option<SegmentData> {
+        let trimmed = output.trim();
+        if trimmed.is_empty() {
+            return None;
+        }
+
+        if trimmed.starts_with('{') {
+            if let Ok(parsed) = serde_json::from_str::<CommandOutput>(trimmed) {
+                let metadata = parsed
+                    .metadata
+                    .into_iter()
+                    .map(|(key, value)| match value {
+                        serde_json::Value::String(s) => (key, s),
+                        other => (key, other.to_string()),
+                    })
+                    .collect();
+
+                return Some(SegmentData {
+                    primary: self.truncate(&parsed.primary),
+                    secondary: self.truncate(&parsed.secondary),
+                    metadata,
+                });
+            }
+        }
+
+        // Plain text: only the first line fits on a status line
+        let first_line = trimmed.lines().next().unwrap_or_default().trim();
+        Some(SegmentData {
+            primary: self.truncate(first_line),
+            secondary: String::new(),
+            metadata: HashMap::new(),
+        })
+    }
+
+    fn truncate(&self, text: &str) -> String {
+        if text.chars().count() <= self.config.max_length {
+            return text.to_string();
+        }
+        let truncated: String = text
+            .chars()
+            .take(self.config.max_length.saturating_sub(1))
+            .collect();
+        format!("{}…", truncated)
+    }
+
+    /// Cache file keyed on the command and working directory
+    fn cache_path(&self, working_dir: &str) -> Option<PathBuf> {
+        let mut hasher = DefaultHasher::new();
+        self.config.command.hash(&mut hasher);
+        self.config.shell.hash(&mut hasher);
+        working_dir.hash(&mut hasher);
+
+        dirs::home_dir().map(|home| {
+            home.join(".claude")
+                .join("ccline")
+                .join("cache")
+                .join(format!("custom-{:016x}.json", hasher.finish()))
+        })
+    }
+
+    fn read_cache(&self, working_dir: &str, now: u64) -> Option<SegmentData> {
+        let content = fs::read_to_string(self.cache_path(working_dir)?).ok()?;
+        let entry: CacheEntry = serde_json::from_str(&content).ok()?;
+
+        if now.saturating_sub(entry.timestamp) >= self.config.cache_ttl {
+            return None;
+        }
+
+        Some(SegmentData {
+            primary: entry.primary,
+            secondary: entry.secondary,
+            metadata: entry.metadata,
+        })
+    }
+
+    fn write_cache(&self, working_dir: &str, now: u64, data: &SegmentData) {
+        let Some(path) = self.cache_path(working_dir) else {
+            return;
+        };
+        let entry = CacheEntry {
+            timestamp: now,
+            primary: data.primary.clone(),
+            secondary: data.secondary.clone(),
+            metadata: data.metadata.clone(),
+        };
+
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(content) = serde_json::to_string(&entry) {
+            let _ = fs::write(path, content);
+        }
+    }
+}
+
+impl Segment for CustomSegment {
+    fn collect(&self, input: &InputData) -> Option<SegmentData> {
+        if self.config.command.trim().is_empty() {
+            return None;
+        }
+
+        let working_dir = input.workspace.current_dir.as_str();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        if self.config.cache_ttl > 0 {
+            if let Some(cached) = self.read_cache(working_dir, now) {
+                return Some(cached);
+            }
+        }
+
+        let input_json = serde_json::to_string(input).ok()?;
+        let data = self.parse_output(&self.run_command(&input_json, working_dir)?)?;
+
+        if self.config.cache_ttl > 0 {
+            self.write_cache(working_dir, now, &data);
+        }
+
+        Some(data)
+    }
+
+    fn id(&self) -> SegmentId {
+        SegmentId::Custom
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn segment(command: &str, max_length: u64) -> CustomSegment {
+        let options = HashMap::from([
+            ("command".to_string(), json!(command)),
+            ("max_length".to_string(), json!(max_length)),
+        ]);
+        CustomSegment::with_config(&options)
+    }
+
+    #[test]
+    fn parses_json_and_plain_text_output() {
+        let segment = segment("true", 8);
+
+        let data = segment
+            .parse_output(r#"{"primary": "deploy", "metadata": {"count": 3, "env": "prod"}}"#)
+            .unwrap();
+        assert_eq!(data.primary, "deploy");
+        assert_eq!(data.secondary, "");
+        assert_eq!(data.metadata["count"], "3");
+        assert_eq!(data.metadata["env"], "prod");
+
+        let data = segment
+            .parse_output("\n  first line of text \nsecond\n")
+            .unwrap();
+        assert_eq!(data.primary, "first l…");
+        assert!(segment.parse_output(" \n").is_none());
+        // Malformed JSON falls back to plain text
+        assert_eq!(segment.parse_output("{oops").unwrap().primary, "{oops");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn runs_command_with_input_on_stdin() {
+        let input = InputData::for_directory("/");
+        let data = segment("grep -o current_dir | head -n 1", 60)
+            .collect(&input)
+            .unwrap();
+        assert_eq!(data.primary, "current_dir");
+
+        assert!(segment("exit 3", 60).collect(&input).is_none());
+    }
+}
@@ -1,7 +1,11 @@
 use super::{Segment, SegmentData};
 use crate::config::{InputData, SegmentId, DirectorySegmentConfig, CaseStyle};
 use std::collections::HashMap;
-use std::path::Path;
+use std::fs;
+use std::path::{Component, Path, PathBuf, MAIN_SEPARATOR_STR};
+
+/// Directories marking a repository root, preferred over project marker files
+const VCS_MARKERS: &[&str] = &[".git", ".jj", ".hg"];
 
 #[derive(Default)]
 pub struct DirectorySegment {
@@ -21,7 +25,15 @@ impl DirectorySegment {
     }
 
     /// Extract and format directory name from path according to configuration
-    fn format_directory_path(&self, path: &str) -> String {
+    fn format_directory_path(&self, path: &str, project_root: Option<&Path>) -> String {
+        if let Some(root) = project_root {
+            return self.format_project_path(root, Path::new(path));
+        }
+
+        if self.config.show_full_path && self.config.abbreviate_components {
+            return self.format_abbreviated_path(Path::new(path));
+        }
+
         let formatted_path = if self.config.abbreviate_home {
             self.abbreviate_home_directory(path)
         } else {
@@ -38,6 +50,123 @@ impl DirectorySegment {
         }
     }
     
+    /// Repository root (nearest `.git`, `.jj` or `.hg`), else the nearest
+    /// directory holding one of the project marker files
+    fn find_project_root(&self, path: &Path) -> Option<PathBuf> {
+        let vcs_root = path
+            .ancestors()
+            .find(|dir| VCS_MARKERS.iter().any(|marker| dir.join(marker).exists()));
+        let root = vcs_root.or_else(|| {
+            path.ancestors().find(|dir| {
+                self.config
+                    .project_markers
+                    .iter()
+                    .any(|marker| dir.join(marker).exists())
+            })
+        });
+        root.map(Path::to_path_buf)
+    }
+
+    /// `project-name/relative/sub/path`
+    fn format_project_path(&self, root: &Path, path: &Path) -> String {
+        let project_name = root
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| root.to_string_lossy().into_owned());
+        let relative = path.strip_prefix(root).unwrap_or(Path::new(""));
+
+        let rendered = self.join_components(project_name, root, relative);
+        self.apply_case_style(&self.apply_length_limit(&rendered))
+    }
+
+    /// Full path with intermediate components shortened, e.g. `~/w/p/src/core`
+    fn format_abbreviated_path(&self, path: &Path) -> String {
+        let home = dirs::home_dir()
+            .filter(|home| self.config.abbreviate_home && path.starts_with(home));
+
+        let rendered = match home {
+            Some(home) => {
+                let relative = path.strip_prefix(&home).unwrap_or(Path::new(""));
+                self.join_components("~".to_string(), &home, relative)
+            }
+            None => {
+                // Root or drive prefix, e.g. "/" or "C:\"
+                let base: PathBuf = path
+                    .components()
+                    .take_while(|c| !matches!(c, Component::Normal(_)))
+                    .collect();
+                let relative = path.strip_prefix(&base).unwrap_or(path);
+                let label = base.to_string_lossy().trim_end_matches(['/', '\\']).to_string();
+                self.join_components(label, &base, relative)
+            }
+        };
+        self.apply_case_style(&self.apply_length_limit(&rendered))
+    }
+
+    /// Join `label` and the components of `relative` (below `base`), abbreviating
+    /// all but the last `full_components` when abbreviation is enabled
+    fn join_components(&self, label: String, base: &Path, relative: &Path) -> String {
+        let names: Vec<String> = relative
+            .iter()
+            .map(|name| name.to_string_lossy().into_owned())
+            .collect();
+        let keep_from = names.len().saturating_sub(self.config.full_components);
+
+        let mut parts = vec![label];
+        let mut parent = base.to_path_buf();
+        for (i, name) in names.iter().enumerate() {
+            if self.config.abbreviate_components && i < keep_from {
+                parts.push(Self::unique_prefix(&parent, name));
+            } else {
+                parts.push(name.clone());
+            }
+            parent.push(name);
+        }
+
+        parts.join(MAIN_SEPARATOR_STR)
+    }
+
+    /// Shortest prefix of `name` that no sibling directory in `parent` shares.
+    /// Hidden directories keep their dot plus at least one character.
+    fn unique_prefix(parent: &Path, name: &str) -> String {
+        let siblings: Vec<String> = fs::read_dir(parent)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter(|entry| entry.file_type().map(|t| t.is_dir()).unwrap_or(false))
+                    .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                    .filter(|sibling| sibling != name)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let chars: Vec<char> = name.chars().collect();
+        let min_len = if name.starts_with('.') { 2 } else { 1 };
+        (min_len..chars.len())
+            .map(|len| chars[..len].iter().collect::<String>())
+            .find(|prefix| !siblings.iter().any(|sibling| sibling.starts_with(prefix.as_str())))
+            .unwrap_or_else(|| name.to_string())
+    }
+
+    /// `file://` URL for a local path, percent-encoding anything outside the unreserved set
+    fn file_url(path: &str) -> String {
+        let path = path.replace('\\', "/");
+        let mut url = String::from("file://");
+        if !path.starts_with('/') {
+            // Windows drive paths become file:///C:/...
+            url.push('/');
+        }
+        for byte in path.bytes() {
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                    url.push(byte as char)
+                }
+                _ => url.push_str(&format!("%{:02X}", byte)),
+            }
+        }
+        url
+    }
+
     /// Abbreviate home directory with ~
     fn abbreviate_home_directory(&self, path: &str) -> String {
         if let Ok(home) = std::env::var("HOME") {
@@ -133,8 +262,14 @@ impl Segment for DirectorySegment {
     fn collect(&self, input: &InputData) -> Option<SegmentData> {
         let current_dir = &input.workspace.current_dir;
 
+        let project_root = if self.config.show_project_root {
+            self.find_project_root(Path::new(current_dir))
+        } else {
+            None
+        };
+
         // Use the new formatting logic
-        let formatted_dir = self.format_directory_path(current_dir);
+        let formatted_dir = self.format_directory_path(current_dir, project_root.as_deref());
 
         // Store configuration and path information in metadata
         let mut metadata = HashMap::new();
@@ -144,6 +279,12 @@ impl Segment for DirectorySegment {
         metadata.insert("abbreviate_home".to_string(), self.config.abbreviate_home.to_string());
         metadata.insert("show_parent".to_string(), self.config.show_parent.to_string());
         metadata.insert("case_style".to_string(), format!("{:?}", self.config.case_style));
+        if let Some(ref root) = project_root {
+            metadata.insert("project_root".to_string(), root.to_string_lossy().into_owned());
+        }
+        if self.config.hyperlink && !current_dir.is_empty() {
+            metadata.insert("hyperlink".to_string(), Self::file_url(current_dir));
+        }
 
         Some(SegmentData {
             primary: formatted_dir,
@@ -156,3 +297,83 @@ impl Segment for DirectorySegment {
         SegmentId::Directory
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_dir::TestDir;
+    use serde_json::json;
+
+    fn segment(options: serde_json::Value) -> DirectorySegment {
+        let options = serde_json::from_value(options).unwrap();
+        DirectorySegment::new().with_config(&options)
+    }
+
+    #[test]
+    fn abbreviates_components_to_unique_prefixes_below_project_root() {
+        let root = TestDir::new("dir");
+        let repo = root.join("shop");
+        let current = repo.join("services").join("api").join("src");
+        for dir in [
+            current.clone(),
+            repo.join(".git"),
+            repo.join("scripts"),
+            repo.join("services").join("app"),
+            repo.join(".config").join("nested"),
+            repo.join(".cache"),
+            root.join("site"),
+        ] {
+            fs::create_dir_all(dir).unwrap();
+        }
+        fs::write(root.join("site").join("package.json"), "{}").unwrap();
+
+        let project = segment(json!({
+            "show_project_root": true,
+            "abbreviate_components": true,
+            "max_length": 100
+        }));
+        let detected = project.find_project_root(&current);
+        let from_marker = project.find_project_root(&root.join("site"));
+        let rendered =
+            project.format_directory_path(current.to_str().unwrap(), detected.as_deref());
+        let hidden = DirectorySegment::unique_prefix(&repo, ".config");
+        let two_full = segment(json!({
+            "show_project_root": true,
+            "abbreviate_components": true,
+            "full_components": 2
+        }))
+        .format_project_path(&repo, &current);
+
+        assert_eq!(detected.as_deref(), Some(repo.as_path()));
+        assert_eq!(from_marker, Some(root.join("site")));
+        assert_eq!(
+            rendered,
+            ["shop", "se", "api", "src"].join(MAIN_SEPARATOR_STR)
+        );
+        assert_eq!(hidden, ".co");
+        assert_eq!(
+            two_full,
+            ["shop", "se", "api", "src"].join(MAIN_SEPARATOR_STR)
+        );
+    }
+
+    #[test]
+    fn formats_name_parent_and_length_limit() {
+        let path = "/home/user/projects/ccometixline";
+        assert_eq!(
+            segment(json!({})).format_directory_path(path, None),
+            "ccometixline"
+        );
+        assert_eq!(
+            segment(json!({"show_parent": true, "max_length": 40}))
+                .format_directory_path(path, None),
+            "projects/ccometixline"
+        );
+        assert_eq!(
+            segment(json!({"max_length": 8, "case_style": "uppercase"}))
+                .format_directory_path(path, None),
+            "...XLINE"
+        );
+        assert_eq!(DirectorySegment::file_url("/tmp/a b"), "file:///tmp/a%20b");
+    }
+}
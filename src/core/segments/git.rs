@@ -1,7 +1,17 @@
+use super::ini::ini_value;
+use super::vcs::{self, RepositoryInfo};
 use super::{Segment, SegmentData};
-use crate::config::{InputData, SegmentId, GitSegmentConfig, GitStatusFormat};
-use std::collections::HashMap;
+use crate::config::{
+    DiffStatsScope, GitSegmentConfig, GitStatusFormat, InputData, SegmentConfig, SegmentId,
+    VcsKind,
+};
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Debug)]
 pub struct GitInfo {
@@ -13,6 +23,34 @@ pub struct GitInfo {
     pub sha: Option<String>,
     pub stash_count: Option<u32>,
     pub tag: Option<String>,
+    pub state: Option<GitRepoState>,
+    /// Name of the linked worktree, None for the main working tree
+    pub worktree: Option<String>,
+    /// Uncommitted lines added and removed
+    pub diff_stats: Option<(u32, u32)>,
+    pub last_commit: Option<LastCommit>,
+    pub upstream: Option<Upstream>,
+    /// Commits ahead of and behind the configured base branch
+    pub base_divergence: Option<(u32, u32)>,
+    /// Web page of the branch on the `origin` remote
+    pub branch_url: Option<String>,
+}
+
+/// Tracking state of the current branch
+#[derive(Debug, PartialEq)]
+pub enum Upstream {
+    Tracking(String),
+    /// Configured upstream whose remote branch no longer exists
+    Gone(String),
+    None,
+}
+
+#[derive(Debug)]
+pub struct LastCommit {
+    /// Seconds since the commit was made
+    pub age_secs: u64,
+    pub subject: String,
+    pub author: String,
 }
 
 #[derive(Debug, PartialEq)]
@@ -27,8 +65,77 @@ pub struct GitStatusCounts {
     pub added: u32,
     pub modified: u32,
     pub deleted: u32,
+    /// Files with changes in the index
+    pub staged: u32,
+    /// Tracked files with changes in the worktree that are not staged
+    pub unstaged: u32,
+    pub untracked: u32,
+    pub renamed: u32,
+    pub conflicted: u32,
+}
+
+/// Operation in progress, as recorded under the git directory
+#[derive(Debug, Clone, PartialEq)]
+pub enum GitRepoState {
+    Rebase { step: Option<u32>, total: Option<u32> },
+    Merge,
+    CherryPick,
+    Revert,
+    Bisect,
+    ApplyMailbox,
+}
+
+impl GitRepoState {
+    /// Key used in metadata and in the `state_colors` option
+    pub fn key(&self) -> &'static str {
+        match self {
+            GitRepoState::Rebase { .. } => "rebase",
+            GitRepoState::Merge => "merge",
+            GitRepoState::CherryPick => "cherry_pick",
+            GitRepoState::Revert => "revert",
+            GitRepoState::Bisect => "bisect",
+            GitRepoState::ApplyMailbox => "am",
+        }
+    }
+
+    fn progress(&self) -> Option<(u32, u32)> {
+        match self {
+            GitRepoState::Rebase {
+                step: Some(step),
+                total: Some(total),
+            } => Some((*step, *total)),
+            _ => None,
+        }
+    }
+}
+
+/// Per-worktree git directory and the repository's common directory
+struct GitDirs {
+    work_tree: PathBuf,
+    git_dir: PathBuf,
+    common_dir: PathBuf,
+}
+
+/// Transcript line, only as far as needed to find file-editing tool calls
+#[derive(Deserialize)]
+struct ToolUseEntry {
+    message: Option<ToolUseMessage>,
+}
+
+#[derive(Deserialize)]
+struct ToolUseMessage {
+    #[serde(default)]
+    content: serde_json::Value,
 }
 
+/// Tools whose calls change files, and the input field holding the path
+const FILE_EDIT_TOOLS: &[(&str, &str)] = &[
+    ("Edit", "file_path"),
+    ("MultiEdit", "file_path"),
+    ("Write", "file_path"),
+    ("NotebookEdit", "notebook_path"),
+];
+
 pub struct GitSegment {
     config: GitSegmentConfig,
 }
@@ -52,13 +159,37 @@ impl GitSegment {
         }
     }
 
-    fn get_git_info(&self, working_dir: &str) -> Option<GitInfo> {
+    fn get_git_info(&self, working_dir: &str, transcript_path: &str) -> Option<GitInfo> {
         if !self.is_git_repository(working_dir) {
             return None;
         }
 
-        let branch = self
-            .get_branch(working_dir)
+        let dirs = Self::find_git_dirs(Path::new(working_dir));
+        let state = dirs.as_ref().and_then(|dirs| Self::get_state(&dirs.git_dir));
+        let worktree = dirs.as_ref().and_then(Self::get_worktree_name);
+
+        let current_branch = self.get_branch(working_dir);
+        let upstream = if self.config.show_upstream {
+            current_branch
+                .as_deref()
+                .map(|branch| self.get_upstream(working_dir, branch))
+        } else {
+            None
+        };
+        let base_divergence = match (self.config.base_branch.as_deref(), current_branch.as_deref()) {
+            (Some(base), Some(branch)) if base != branch => {
+                self.get_base_divergence(working_dir, base)
+            }
+            _ => None,
+        };
+
+        // HEAD is detached while rebasing; show the branch being rebased instead
+        let branch = current_branch
+            .or_else(|| {
+                dirs.as_ref()
+                    .filter(|_| matches!(state, Some(GitRepoState::Rebase { .. })))
+                    .and_then(|dirs| Self::get_rebase_branch(&dirs.git_dir))
+            })
             .unwrap_or_else(|| "detached".to_string());
         let (status, status_counts) = self.get_status_with_counts(working_dir);
         let (ahead, behind) = self.get_ahead_behind(working_dir);
@@ -80,6 +211,36 @@ impl GitSegment {
             None
         };
 
+        let diff_stats = if self.config.show_diff_stats {
+            match self.config.diff_stats_scope {
+                DiffStatsScope::All => self.get_diff_stats(working_dir, None),
+                DiffStatsScope::Session => dirs.as_ref().and_then(|dirs| {
+                    let paths = Self::get_session_files(Path::new(transcript_path), &dirs.work_tree);
+                    self.get_diff_stats(working_dir, Some(&paths))
+                }),
+            }
+        } else {
+            None
+        };
+
+        let wants_commit = self.config.show_commit_age
+            || self.config.show_commit_subject
+            || self.config.show_commit_author
+            || self.config.stale_after_minutes > 0;
+        let last_commit = if wants_commit {
+            self.get_last_commit(working_dir)
+        } else {
+            None
+        };
+
+        let branch_url = if self.config.hyperlink && branch != "detached" {
+            dirs.as_ref()
+                .and_then(|dirs| Self::get_origin_web_url(&dirs.common_dir))
+                .map(|web_url| Self::branch_web_url(&web_url, &branch))
+        } else {
+            None
+        };
+
         Some(GitInfo {
             branch: self.format_branch_name(branch),
             status,
@@ -89,6 +250,161 @@ impl GitSegment {
             sha,
             stash_count,
             tag,
+            state,
+            worktree,
+            diff_stats,
+            last_commit,
+            upstream,
+            base_divergence,
+            branch_url,
+        })
+    }
+
+    /// Web URL of the `origin` remote, read from the repository config.
+    /// `git@host:owner/repo.git` and `ssh://` remotes map to `https://host/owner/repo`.
+    fn get_origin_web_url(common_dir: &Path) -> Option<String> {
+        let content = fs::read_to_string(common_dir.join("config")).ok()?;
+        let url = ini_value(&content, "remote \"origin\"", "url")?;
+        let url = url.trim_matches('"');
+
+        let (host, path) = if let Some(rest) = url
+            .strip_prefix("https://")
+            .or_else(|| url.strip_prefix("http://"))
+            .or_else(|| url.strip_prefix("ssh://"))
+            .or_else(|| url.strip_prefix("git://"))
+        {
+            rest.split_once('/')?
+        } else if let Some((user_host, path)) = url.split_once(':') {
+            // scp-like syntax; anything with a slash before the colon is a local path
+            if user_host.contains('/') {
+                return None;
+            }
+            (user_host, path)
+        } else {
+            return None;
+        };
+
+        // Drop credentials or user and port
+        let host = host.rsplit('@').next()?;
+        let host = host.split(':').next()?;
+        let path = path.trim_start_matches('/').trim_end_matches('/');
+        let path = path.strip_suffix(".git").unwrap_or(path);
+        if host.is_empty() || path.is_empty() {
+            return None;
+        }
+
+        Some(format!("https://{}/{}", host, path))
+    }
+
+    /// Branch page, using the URL layout of the hosting service
+    fn branch_web_url(web_url: &str, branch: &str) -> String {
+        // Branch names may hold URL metacharacters; keep `/` for nested names
+        let mut encoded = String::new();
+        for byte in branch.bytes() {
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                    encoded.push(byte as char)
+                }
+                _ => encoded.push_str(&format!("%{:02X}", byte)),
+            }
+        }
+        let branch = encoded.as_str();
+
+        if web_url.contains("gitlab") {
+            format!("{}/-/tree/{}", web_url, branch)
+        } else if web_url.contains("bitbucket") {
+            format!("{}/src/{}", web_url, branch)
+        } else {
+            format!("{}/tree/{}", web_url, branch)
+        }
+    }
+
+    /// Locate the git directories by walking up from the working directory.
+    /// In a linked worktree `.git` is a file pointing at `<common>/worktrees/<name>`.
+    fn find_git_dirs(working_dir: &Path) -> Option<GitDirs> {
+        let dot_git = working_dir
+            .ancestors()
+            .map(|dir| dir.join(".git"))
+            .find(|path| path.exists())?;
+
+        let work_tree = dot_git.parent()?.to_path_buf();
+        let git_dir = if dot_git.is_dir() {
+            dot_git
+        } else {
+            let content = fs::read_to_string(&dot_git).ok()?;
+            let target = content.trim().strip_prefix("gitdir:")?.trim();
+            dot_git.parent()?.join(target)
+        };
+
+        let common_dir = fs::read_to_string(git_dir.join("commondir"))
+            .ok()
+            .map(|dir| git_dir.join(dir.trim()))
+            .unwrap_or_else(|| git_dir.clone());
+
+        Some(GitDirs {
+            work_tree,
+            git_dir,
+            common_dir,
+        })
+    }
+
+    fn get_worktree_name(dirs: &GitDirs) -> Option<String> {
+        let git_dir = fs::canonicalize(&dirs.git_dir).ok()?;
+        let common_dir = fs::canonicalize(&dirs.common_dir).ok()?;
+        if git_dir == common_dir {
+            return None;
+        }
+        git_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+    }
+
+    /// Detect an in-progress operation from the marker files git leaves behind
+    fn get_state(git_dir: &Path) -> Option<GitRepoState> {
+        let read_number = |path: PathBuf| -> Option<u32> {
+            fs::read_to_string(path).ok()?.trim().parse().ok()
+        };
+
+        let rebase_merge = git_dir.join("rebase-merge");
+        if rebase_merge.is_dir() {
+            return Some(GitRepoState::Rebase {
+                step: read_number(rebase_merge.join("msgnum")),
+                total: read_number(rebase_merge.join("end")),
+            });
+        }
+
+        let rebase_apply = git_dir.join("rebase-apply");
+        if rebase_apply.is_dir() {
+            if rebase_apply.join("applying").exists() {
+                return Some(GitRepoState::ApplyMailbox);
+            }
+            return Some(GitRepoState::Rebase {
+                step: read_number(rebase_apply.join("next")),
+                total: read_number(rebase_apply.join("last")),
+            });
+        }
+
+        if git_dir.join("MERGE_HEAD").exists() {
+            Some(GitRepoState::Merge)
+        } else if git_dir.join("CHERRY_PICK_HEAD").exists() {
+            Some(GitRepoState::CherryPick)
+        } else if git_dir.join("REVERT_HEAD").exists() {
+            Some(GitRepoState::Revert)
+        } else if git_dir.join("BISECT_LOG").exists() {
+            Some(GitRepoState::Bisect)
+        } else {
+            None
+        }
+    }
+
+    fn get_rebase_branch(git_dir: &Path) -> Option<String> {
+        ["rebase-merge", "rebase-apply"].iter().find_map(|dir| {
+            let head_name = fs::read_to_string(git_dir.join(dir).join("head-name")).ok()?;
+            let head_name = head_name.trim();
+            head_name
+                .strip_prefix("refs/heads/")
+                .or(Some(head_name).filter(|name| !name.is_empty() && *name != "detached HEAD"))
+                .map(|name| name.to_string())
         })
     }
 
@@ -145,37 +461,8 @@ impl GitSegment {
                     return (GitStatus::Clean, GitStatusCounts::default());
                 }
 
-                let mut counts = GitStatusCounts::default();
-                let mut has_conflicts = false;
-
-                for line in status_text.lines() {
-                    if line.len() < 2 { continue; }
-                    
-                    let staged = line.chars().nth(0).unwrap_or(' ');
-                    let unstaged = line.chars().nth(1).unwrap_or(' ');
-                    
-                    // Check for conflicts
-                    if staged == 'U' && unstaged == 'U' 
-                        || staged == 'A' && unstaged == 'A'
-                        || staged == 'D' && unstaged == 'D' {
-                        has_conflicts = true;
-                        continue;
-                    }
-                    
-                    // Count changes
-                    if staged == 'A' || unstaged == 'A' {
-                        counts.added += 1;
-                    } else if staged == 'M' || unstaged == 'M' {
-                        counts.modified += 1;
-                    } else if staged == 'D' || unstaged == 'D' {
-                        counts.deleted += 1;
-                    } else if staged != ' ' || unstaged != ' ' {
-                        // Other changes count as modified
-                        counts.modified += 1;
-                    }
-                }
-
-                let status = if has_conflicts {
+                let counts = Self::parse_porcelain(&status_text);
+                let status = if counts.conflicted > 0 {
                     GitStatus::Conflicts
                 } else {
                     GitStatus::Dirty
@@ -187,12 +474,211 @@ impl GitSegment {
         }
     }
 
+    /// Tally `git status --porcelain` lines. The first column is the index,
+    /// the second the worktree, so a file can be both staged and unstaged.
+    fn parse_porcelain(status_text: &str) -> GitStatusCounts {
+        let mut counts = GitStatusCounts::default();
+
+        for line in status_text.lines() {
+            let mut chars = line.chars();
+            let (Some(staged), Some(unstaged)) = (chars.next(), chars.next()) else {
+                continue;
+            };
+
+            match (staged, unstaged) {
+                ('?', '?') => {
+                    counts.untracked += 1;
+                    counts.modified += 1;
+                    continue;
+                }
+                ('!', '!') => continue,
+                ('U', _) | (_, 'U') | ('A', 'A') | ('D', 'D') => {
+                    counts.conflicted += 1;
+                    continue;
+                }
+                _ => {}
+            }
+
+            if staged != ' ' {
+                counts.staged += 1;
+            }
+            if unstaged != ' ' {
+                counts.unstaged += 1;
+            }
+            if staged == 'R' || unstaged == 'R' {
+                counts.renamed += 1;
+            }
+
+            if staged == 'A' || unstaged == 'A' {
+                counts.added += 1;
+            } else if staged == 'M' || unstaged == 'M' {
+                counts.modified += 1;
+            } else if staged == 'D' || unstaged == 'D' {
+                counts.deleted += 1;
+            } else {
+                // Other changes count as modified
+                counts.modified += 1;
+            }
+        }
+
+        counts
+    }
+
+    /// Lines added and removed relative to HEAD, optionally limited to `paths`.
+    /// Untracked files among `paths` count as entirely added.
+    fn get_diff_stats(&self, working_dir: &str, paths: Option<&[PathBuf]>) -> Option<(u32, u32)> {
+        if paths.is_some_and(|paths| paths.is_empty()) {
+            return Some((0, 0));
+        }
+
+        let mut command = Command::new("git");
+        command
+            .args(["diff", "--numstat", "HEAD", "--"])
+            .current_dir(working_dir);
+        if let Some(paths) = paths {
+            command.args(paths);
+        }
+        let output = command.output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+
+        let mut added = 0;
+        let mut removed = 0;
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let mut columns = line.split('\t');
+            // Binary files report "-" instead of line counts
+            added += columns.next().and_then(|n| n.parse::<u32>().ok()).unwrap_or(0);
+            removed += columns.next().and_then(|n| n.parse::<u32>().ok()).unwrap_or(0);
+        }
+
+        if let Some(paths) = paths {
+            added += self.count_untracked_lines(working_dir, paths);
+        }
+
+        Some((added, removed))
+    }
+
+    fn count_untracked_lines(&self, working_dir: &str, paths: &[PathBuf]) -> u32 {
+        let Ok(output) = Command::new("git")
+            .args(["ls-files", "--others", "--exclude-standard", "-z", "--"])
+            .args(paths)
+            .current_dir(working_dir)
+            .output()
+        else {
+            return 0;
+        };
+        if !output.status.success() {
+            return 0;
+        }
+
+        // Names are relative to the working directory
+        String::from_utf8_lossy(&output.stdout)
+            .split('\0')
+            .filter(|name| !name.is_empty())
+            .filter_map(|name| fs::read_to_string(Path::new(working_dir).join(name)).ok())
+            .map(|content| content.lines().count() as u32)
+            .sum()
+    }
+
+    /// Files inside the work tree that Edit/Write tool calls touched in this session
+    fn get_session_files(transcript_path: &Path, work_tree: &Path) -> Vec<PathBuf> {
+        let Ok(file) = fs::File::open(transcript_path) else {
+            return Vec::new();
+        };
+
+        let mut files = BTreeSet::new();
+        for line in BufReader::new(file).lines().map_while(Result::ok) {
+            // Cheap filter before parsing; most lines carry no tool calls
+            if !line.contains("\"tool_use\"") {
+                continue;
+            }
+            let Ok(entry) = serde_json::from_str::<ToolUseEntry>(&line) else {
+                continue;
+            };
+            let Some(blocks) = entry.message.as_ref().and_then(|m| m.content.as_array()) else {
+                continue;
+            };
+
+            for block in blocks {
+                if block.get("type").and_then(|t| t.as_str()) != Some("tool_use") {
+                    continue;
+                }
+                let name = block.get("name").and_then(|n| n.as_str()).unwrap_or_default();
+                let Some((_, field)) = FILE_EDIT_TOOLS.iter().find(|(tool, _)| *tool == name) else {
+                    continue;
+                };
+                if let Some(path) = block
+                    .get("input")
+                    .and_then(|input| input.get(*field))
+                    .and_then(|p| p.as_str())
+                {
+                    files.insert(PathBuf::from(path));
+                }
+            }
+        }
+
+        // git refuses pathspecs outside the repository
+        files
+            .into_iter()
+            .filter(|path| path.starts_with(work_tree))
+            .collect()
+    }
+
     fn get_ahead_behind(&self, working_dir: &str) -> (u32, u32) {
         let ahead = self.get_commit_count(working_dir, "@{u}..HEAD");
         let behind = self.get_commit_count(working_dir, "HEAD..@{u}");
         (ahead, behind)
     }
 
+    fn get_upstream(&self, working_dir: &str, branch: &str) -> Upstream {
+        let output = Command::new("git")
+            .args([
+                "for-each-ref",
+                "--format=%(upstream:short)%00%(upstream:track)",
+                &format!("refs/heads/{}", branch),
+            ])
+            .current_dir(working_dir)
+            .output();
+
+        let Ok(output) = output else {
+            return Upstream::None;
+        };
+        let text = String::from_utf8_lossy(&output.stdout);
+        let mut fields = text.trim_end().splitn(2, '\0');
+        let name = fields.next().unwrap_or_default().to_string();
+        let track = fields.next().unwrap_or_default();
+
+        if !output.status.success() || name.is_empty() {
+            Upstream::None
+        } else if track == "[gone]" {
+            Upstream::Gone(name)
+        } else {
+            Upstream::Tracking(name)
+        }
+    }
+
+    /// Ahead/behind against `base`, falling back to `origin/<base>` when there is no local branch
+    fn get_base_divergence(&self, working_dir: &str, base: &str) -> Option<(u32, u32)> {
+        [base.to_string(), format!("origin/{}", base)]
+            .iter()
+            .find_map(|base_ref| {
+                let output = Command::new("git")
+                    .args(["rev-list", "--left-right", "--count", &format!("{}...HEAD", base_ref)])
+                    .current_dir(working_dir)
+                    .output()
+                    .ok()?;
+                if !output.status.success() {
+                    return None;
+                }
+                let text = String::from_utf8(output.stdout).ok()?;
+                let mut counts = text.split_whitespace().map(|n| n.parse::<u32>().ok());
+                let behind = counts.next()??;
+                let ahead = counts.next()??;
+                Some((ahead, behind))
+            })
+    }
+
     fn get_commit_count(&self, working_dir: &str, range: &str) -> u32 {
         let output = Command::new("git")
             .args(["rev-list", "--count", range])
@@ -227,6 +713,44 @@ impl GitSegment {
         }
     }
     
+    fn get_last_commit(&self, working_dir: &str) -> Option<LastCommit> {
+        let output = Command::new("git")
+            .args(["log", "-1", "--format=%ct%x00%an%x00%s"])
+            .current_dir(working_dir)
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+
+        let text = String::from_utf8_lossy(&output.stdout);
+        let mut fields = text.trim_end().splitn(3, '\0');
+        let timestamp: u64 = fields.next()?.parse().ok()?;
+        let author = fields.next().unwrap_or_default().to_string();
+        let subject = fields.next().unwrap_or_default().to_string();
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(timestamp);
+
+        Some(LastCommit {
+            age_secs: now.saturating_sub(timestamp),
+            subject,
+            author,
+        })
+    }
+
+    fn is_stale(&self, git_info: &GitInfo) -> bool {
+        if self.config.stale_after_minutes == 0 || git_info.status == GitStatus::Clean {
+            return false;
+        }
+        git_info
+            .last_commit
+            .as_ref()
+            .is_some_and(|commit| commit.age_secs >= self.config.stale_after_minutes * 60)
+    }
+
     fn get_stash_count(&self, working_dir: &str) -> Option<u32> {
         let output = Command::new("git")
             .args(["stash", "list"])
@@ -259,21 +783,109 @@ impl GitSegment {
     }
     
     fn format_branch_name(&self, branch: String) -> String {
-        if branch.len() > self.config.branch_max_length {
-            let truncated = &branch[..self.config.branch_max_length.saturating_sub(3)];
+        // Count chars, not bytes: jj and hg names may be non-ASCII
+        let max = self.config.branch_max_length;
+        if branch.chars().count() > max {
+            let truncated: String = branch.chars().take(max.saturating_sub(3)).collect();
             format!("{}...", truncated)
         } else {
             branch
         }
     }
     
+    fn format_state(&self, state: &GitRepoState) -> String {
+        let label = match self.config.status_format {
+            GitStatusFormat::Symbols | GitStatusFormat::Count => match state {
+                GitRepoState::Rebase { .. } => "REBASE",
+                GitRepoState::Merge => "MERGING",
+                GitRepoState::CherryPick => "CHERRY-PICKING",
+                GitRepoState::Revert => "REVERTING",
+                GitRepoState::Bisect => "BISECTING",
+                GitRepoState::ApplyMailbox => "AM",
+            },
+            GitStatusFormat::Text => match state {
+                GitRepoState::Rebase { .. } => "rebasing",
+                GitRepoState::Merge => "merging",
+                GitRepoState::CherryPick => "cherry-picking",
+                GitRepoState::Revert => "reverting",
+                GitRepoState::Bisect => "bisecting",
+                GitRepoState::ApplyMailbox => "applying",
+            },
+        };
+
+        match state.progress() {
+            Some((step, total)) => format!("{} {}/{}", label, step, total),
+            None => label.to_string(),
+        }
+    }
+
+    fn format_age(&self, secs: u64) -> String {
+        let age = match secs {
+            0..=59 => format!("{}s", secs),
+            60..=3_599 => format!("{}m", secs / 60),
+            3_600..=86_399 => format!("{}h", secs / 3_600),
+            86_400..=604_799 => format!("{}d", secs / 86_400),
+            _ => format!("{}w", secs / 604_800),
+        };
+        match self.config.status_format {
+            GitStatusFormat::Text => format!("{} ago", age),
+            _ => format!("\u{23f1} {}", age),
+        }
+    }
+
+    fn format_commit(&self, commit: &LastCommit) -> String {
+        let mut parts = Vec::new();
+        if self.config.show_commit_age {
+            parts.push(self.format_age(commit.age_secs));
+        }
+        if self.config.show_commit_subject && !commit.subject.is_empty() {
+            let max = self.config.subject_max_length;
+            if commit.subject.chars().count() > max {
+                let truncated: String = commit.subject.chars().take(max.saturating_sub(1)).collect();
+                parts.push(format!("{}…", truncated));
+            } else {
+                parts.push(commit.subject.clone());
+            }
+        }
+        if self.config.show_commit_author && !commit.author.is_empty() {
+            parts.push(format!("by {}", commit.author));
+        }
+        parts.join(" ")
+    }
+
+    fn format_upstream(&self, upstream: &Upstream) -> String {
+        let text_format = self.config.status_format == GitStatusFormat::Text;
+        match (upstream, text_format) {
+            (Upstream::Tracking(name), false) => format!("→{}", name),
+            (Upstream::Tracking(name), true) => format!("upstream:{}", name),
+            (Upstream::Gone(name), false) => format!("→{} gone", name),
+            (Upstream::Gone(name), true) => format!("upstream:{} gone", name),
+            (Upstream::None, false) => "→∅".to_string(),
+            (Upstream::None, true) => "no upstream".to_string(),
+        }
+    }
+
+    fn format_base_divergence(&self, base: &str, ahead: u32, behind: u32) -> String {
+        match self.config.status_format {
+            GitStatusFormat::Text => format!("{} ahead:{} behind:{}", base, ahead, behind),
+            _ => format!("{}:↑{}↓{}", base, ahead, behind),
+        }
+    }
+
+    fn format_worktree(&self, worktree: &str) -> String {
+        match self.config.status_format {
+            GitStatusFormat::Text => format!("worktree:{}", worktree),
+            _ => format!("wt:{}", worktree),
+        }
+    }
+
     fn format_status(&self, status: &GitStatus, changes: &GitStatusCounts) -> String {
         if self.config.hide_clean_status && *status == GitStatus::Clean {
             return String::new();
         }
         
         match self.config.status_format {
-            GitStatusFormat::Symbols => {
+            GitStatusFormat::Symbols if !self.config.status_breakdown => {
                 match status {
                     GitStatus::Clean => "✓".to_string(),
                     GitStatus::Dirty => {
@@ -286,6 +898,20 @@ impl GitSegment {
                     GitStatus::Conflicts => "⚠".to_string(),
                 }
             },
+            GitStatusFormat::Symbols => {
+                match status {
+                    GitStatus::Clean => "✓".to_string(),
+                    GitStatus::Dirty | GitStatus::Conflicts => {
+                        let mut parts = Vec::new();
+                        if changes.conflicted > 0 { parts.push(format!("⚠{}", changes.conflicted)); }
+                        if changes.staged > 0 { parts.push(format!("+{}", changes.staged)); }
+                        if changes.unstaged > 0 { parts.push(format!("~{}", changes.unstaged)); }
+                        if changes.untracked > 0 { parts.push(format!("?{}", changes.untracked)); }
+                        if changes.renamed > 0 { parts.push(format!("»{}", changes.renamed)); }
+                        if parts.is_empty() { "●".to_string() } else { parts.join(" ") }
+                    },
+                }
+            },
             GitStatusFormat::Text => {
                 match status {
                     GitStatus::Clean => "clean".to_string(),
@@ -299,7 +925,7 @@ impl GitSegment {
                     GitStatus::Conflicts => "conflicts".to_string(),
                 }
             },
-            GitStatusFormat::Count => {
+            GitStatusFormat::Count if !self.config.status_breakdown => {
                 match status {
                     GitStatus::Clean => "clean".to_string(),
                     GitStatus::Dirty => {
@@ -313,15 +939,128 @@ impl GitSegment {
                     GitStatus::Conflicts => "conflicts".to_string(),
                 }
             },
+            GitStatusFormat::Count => {
+                match status {
+                    GitStatus::Clean => "clean".to_string(),
+                    GitStatus::Dirty | GitStatus::Conflicts => {
+                        let breakdown = [
+                            (changes.conflicted, "conflicted"),
+                            (changes.staged, "staged"),
+                            (changes.unstaged, "unstaged"),
+                            (changes.untracked, "untracked"),
+                            (changes.renamed, "renamed"),
+                        ];
+                        let parts: Vec<String> = breakdown
+                            .iter()
+                            .filter(|(count, _)| *count > 0)
+                            .map(|(count, label)| format!("{} {}", count, label))
+                            .collect();
+                        if parts.is_empty() {
+                            "dirty".to_string()
+                        } else {
+                            format!("({})", parts.join(", "))
+                        }
+                    },
+                }
+            },
+        }
+    }
+}
+
+impl RepositoryInfo for GitInfo {
+    fn vcs(&self) -> VcsKind {
+        VcsKind::Git
+    }
+
+    fn head(&self) -> &str {
+        &self.branch
+    }
+
+    fn status(&self) -> &GitStatus {
+        &self.status
+    }
+
+    fn status_counts(&self) -> &GitStatusCounts {
+        &self.status_counts
+    }
+
+    fn change_id(&self) -> Option<&str> {
+        self.sha.as_deref()
+    }
+}
+
+impl GitSegment {
+    /// Segment data for jj and Mercurial, using the same slots and metadata keys as git
+    fn collect_repository(&self, info: &dyn RepositoryInfo) -> SegmentData {
+        let counts = info.status_counts();
+
+        let mut metadata = HashMap::new();
+        metadata.insert("vcs".to_string(), info.vcs().name().to_string());
+        metadata.insert("branch".to_string(), info.head().to_string());
+        metadata.insert("status".to_string(), format!("{:?}", info.status()));
+        metadata.insert("added".to_string(), counts.added.to_string());
+        metadata.insert("modified".to_string(), counts.modified.to_string());
+        metadata.insert("deleted".to_string(), counts.deleted.to_string());
+        metadata.insert("staged".to_string(), counts.staged.to_string());
+        metadata.insert("unstaged".to_string(), counts.unstaged.to_string());
+        metadata.insert("untracked".to_string(), counts.untracked.to_string());
+        metadata.insert("renamed".to_string(), counts.renamed.to_string());
+        metadata.insert("conflicted".to_string(), counts.conflicted.to_string());
+        // Keys git always sets, so rules work the same for every VCS
+        metadata.insert("ahead".to_string(), "0".to_string());
+        metadata.insert("behind".to_string(), "0".to_string());
+        metadata.insert("stale".to_string(), "false".to_string());
+        if let Some(change_id) = info.change_id() {
+            metadata.insert("change_id".to_string(), change_id.to_string());
+        }
+
+        let mut status_parts = Vec::new();
+        let status_str = self.format_status(info.status(), counts);
+        if !status_str.is_empty() {
+            status_parts.push(status_str);
+        }
+
+        // The change id takes the SHA slot
+        if self.config.show_sha {
+            if let Some(change_id) = info.change_id() {
+                status_parts.push(format!("@{}", change_id));
+            }
+        }
+
+        SegmentData {
+            primary: self.format_branch_name(info.head().to_string()),
+            secondary: status_parts.join(" "),
+            metadata,
         }
     }
 }
 
 impl Segment for GitSegment {
     fn collect(&self, input: &InputData) -> Option<SegmentData> {
-        let git_info = self.get_git_info(&input.workspace.current_dir)?;
+        let working_dir = &input.workspace.current_dir;
+        // A missing jj or hg binary falls through to git, e.g. in a colocated jj repo
+        match vcs::detect(Path::new(working_dir), &self.config.vcs_order) {
+            Some((VcsKind::Jujutsu, _)) => {
+                if let Some(info) = vcs::jj_info(working_dir) {
+                    return Some(self.collect_repository(&info));
+                }
+            }
+            Some((VcsKind::Mercurial, root)) => {
+                if let Some(info) = vcs::hg_info(working_dir, &root) {
+                    return Some(self.collect_repository(&info));
+                }
+            }
+            // Git keeps its own detection, which also honours GIT_DIR and friends
+            _ => {}
+        }
+        if !self.config.vcs_order.contains(&VcsKind::Git) {
+            return None;
+        }
+
+        let git_info = self.get_git_info(working_dir, &input.transcript_path)?;
 
         let mut metadata = HashMap::new();
+        metadata.insert("vcs".to_string(), VcsKind::Git.name().to_string());
         metadata.insert("branch".to_string(), git_info.branch.clone());
         metadata.insert("status".to_string(), format!("{:?}", git_info.status));
         metadata.insert("ahead".to_string(), git_info.ahead.to_string());
@@ -329,6 +1068,11 @@ impl Segment for GitSegment {
         metadata.insert("added".to_string(), git_info.status_counts.added.to_string());
         metadata.insert("modified".to_string(), git_info.status_counts.modified.to_string());
         metadata.insert("deleted".to_string(), git_info.status_counts.deleted.to_string());
+        metadata.insert("staged".to_string(), git_info.status_counts.staged.to_string());
+        metadata.insert("unstaged".to_string(), git_info.status_counts.unstaged.to_string());
+        metadata.insert("untracked".to_string(), git_info.status_counts.untracked.to_string());
+        metadata.insert("renamed".to_string(), git_info.status_counts.renamed.to_string());
+        metadata.insert("conflicted".to_string(), git_info.status_counts.conflicted.to_string());
 
         if let Some(ref sha) = git_info.sha {
             metadata.insert("sha".to_string(), sha.clone());
@@ -339,10 +1083,56 @@ impl Segment for GitSegment {
         if let Some(ref tag) = git_info.tag {
             metadata.insert("tag".to_string(), tag.clone());
         }
+        if let Some(ref state) = git_info.state {
+            metadata.insert("state".to_string(), state.key().to_string());
+            if let Some((step, total)) = state.progress() {
+                metadata.insert("state_step".to_string(), step.to_string());
+                metadata.insert("state_total".to_string(), total.to_string());
+            }
+        }
+        if let Some(ref worktree) = git_info.worktree {
+            metadata.insert("worktree".to_string(), worktree.clone());
+        }
+        if let Some(ref url) = git_info.branch_url {
+            metadata.insert("hyperlink".to_string(), url.clone());
+        }
+        if let Some(ref upstream) = git_info.upstream {
+            let (name, gone) = match upstream {
+                Upstream::Tracking(name) => (name.as_str(), false),
+                Upstream::Gone(name) => (name.as_str(), true),
+                Upstream::None => ("", false),
+            };
+            metadata.insert("upstream".to_string(), name.to_string());
+            metadata.insert("upstream_gone".to_string(), gone.to_string());
+        }
+        if let (Some(base), Some((ahead, behind))) =
+            (self.config.base_branch.as_ref(), git_info.base_divergence)
+        {
+            metadata.insert("base_branch".to_string(), base.clone());
+            metadata.insert("base_ahead".to_string(), ahead.to_string());
+            metadata.insert("base_behind".to_string(), behind.to_string());
+        }
+        if let Some(ref commit) = git_info.last_commit {
+            metadata.insert("commit_age".to_string(), commit.age_secs.to_string());
+            metadata.insert("commit_subject".to_string(), commit.subject.clone());
+            metadata.insert("commit_author".to_string(), commit.author.clone());
+        }
+        metadata.insert("stale".to_string(), self.is_stale(&git_info).to_string());
+        if let Some((added, removed)) = git_info.diff_stats {
+            metadata.insert("lines_added".to_string(), added.to_string());
+            metadata.insert("lines_removed".to_string(), removed.to_string());
+        }
 
         let primary = git_info.branch;
         let mut status_parts = Vec::new();
 
+        // In-progress operation comes first, it matters more than the file counts
+        if self.config.show_state {
+            if let Some(ref state) = git_info.state {
+                status_parts.push(self.format_state(state));
+            }
+        }
+
         // Format status based on configuration
         let status_str = self.format_status(&git_info.status, &git_info.status_counts);
         if !status_str.is_empty() {
@@ -359,6 +1149,26 @@ impl Segment for GitSegment {
             }
         }
 
+        if let Some(ref upstream) = git_info.upstream {
+            status_parts.push(self.format_upstream(upstream));
+        }
+
+        // Drift from the base branch, e.g. a feature branch against main
+        if let (Some(base), Some((ahead, behind))) =
+            (self.config.base_branch.as_deref(), git_info.base_divergence)
+        {
+            if ahead > 0 || behind > 0 {
+                status_parts.push(self.format_base_divergence(base, ahead, behind));
+            }
+        }
+
+        // Add uncommitted line counts if enabled
+        if let Some((added, removed)) = git_info.diff_stats {
+            if added > 0 || removed > 0 {
+                status_parts.push(format!("+{} \u{2212}{}", added, removed));
+            }
+        }
+
         // Add SHA if enabled
         if let Some(ref sha) = git_info.sha {
             status_parts.push(format!("@{}", sha));
@@ -374,6 +1184,20 @@ impl Segment for GitSegment {
             status_parts.push(format!("{{{}}}", stash_count));
         }
 
+        // Add last commit age, subject and author if enabled
+        if let Some(ref commit) = git_info.last_commit {
+            let commit_str = self.format_commit(commit);
+            if !commit_str.is_empty() {
+                status_parts.push(commit_str);
+            }
+        }
+
+        if self.config.show_worktree {
+            if let Some(ref worktree) = git_info.worktree {
+                status_parts.push(self.format_worktree(worktree));
+            }
+        }
+
         Some(SegmentData {
             primary,
             secondary: status_parts.join(" "),
@@ -384,4 +1208,315 @@ impl Segment for GitSegment {
     fn id(&self) -> SegmentId {
         SegmentId::Git
     }
+
+    fn resolve_style(&self, config: &mut SegmentConfig, data: &SegmentData) {
+        if data.metadata.get("stale").is_some_and(|stale| stale == "true") {
+            if let Some(ref colors) = self.config.stale_colors {
+                config.colors.overlay(colors);
+            }
+        }
+
+        // An operation in progress matters more than staleness
+        if !self.config.show_state {
+            return;
+        }
+        if let Some(colors) = data
+            .metadata
+            .get("state")
+            .and_then(|state| self.config.state_colors.get(state))
+        {
+            config.colors.overlay(colors);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_dir::TestDir;
+
+    #[test]
+    fn truncates_branch_names_on_char_boundaries() {
+        let options = HashMap::from([("branch_max_length".to_string(), serde_json::json!(6))]);
+        let segment = GitSegment::with_config(&options);
+
+        assert_eq!(segment.format_branch_name("main".to_string()), "main");
+        assert_eq!(
+            segment.format_branch_name("功能-分支-名称".to_string()),
+            "功能-..."
+        );
+        assert_eq!(
+            segment.format_branch_name("featüre/x".to_string()),
+            "fea..."
+        );
+    }
+
+    #[test]
+    fn branch_web_url_percent_encodes_the_branch() {
+        assert_eq!(
+            GitSegment::branch_web_url("https://github.com/o/r", "feat/a b#1?x"),
+            "https://github.com/o/r/tree/feat/a%20b%231%3Fx"
+        );
+        assert_eq!(
+            GitSegment::branch_web_url("https://gitlab.com/o/r", "main"),
+            "https://gitlab.com/o/r/-/tree/main"
+        );
+    }
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(["-c", "commit.gpgsign=false"])
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?}", args);
+    }
+
+    /// Fresh repository on `main` with one commit of a three-line `file.txt`
+    fn temp_repo(name: &str) -> TestDir {
+        let dir = TestDir::new(&format!("git-{}", name));
+        fs::write(dir.join("file.txt"), "one\ntwo\nthree\n").unwrap();
+        git(&dir, &["init", "-q", "-b", "main"]);
+        git(&dir, &["add", "."]);
+        git(&dir, &["commit", "-q", "-m", "Initial commit"]);
+        dir
+    }
+
+    #[test]
+    fn porcelain_separates_index_and_worktree() {
+        let status = "M  staged.rs\n M unstaged.rs\nMM both.rs\nR  old.rs -> new.rs\n?? new.txt\nUU conflict.rs\nAU added_by_us.rs\n";
+        let counts = GitSegment::parse_porcelain(status);
+
+        assert_eq!(counts.staged, 3);
+        assert_eq!(counts.unstaged, 2);
+        assert_eq!(counts.untracked, 1);
+        assert_eq!(counts.renamed, 1);
+        assert_eq!(counts.conflicted, 2);
+    }
+
+    #[test]
+    fn detects_operations_and_linked_worktrees_from_git_dir() {
+        let root = TestDir::new("git-state");
+        let git_dir = root.join("repo").join(".git");
+        let rebase = git_dir.join("rebase-merge");
+        fs::create_dir_all(&rebase).unwrap();
+        fs::write(rebase.join("msgnum"), "3\n").unwrap();
+        fs::write(rebase.join("end"), "7\n").unwrap();
+        fs::write(rebase.join("head-name"), "refs/heads/feature\n").unwrap();
+        let rebasing = GitSegment::get_state(&git_dir);
+        let rebase_branch = GitSegment::get_rebase_branch(&git_dir);
+
+        fs::remove_dir_all(&rebase).unwrap();
+        fs::write(git_dir.join("CHERRY_PICK_HEAD"), "").unwrap();
+        let cherry_picking = GitSegment::get_state(&git_dir);
+
+        // A linked worktree's .git file points at <common>/worktrees/<name>
+        let linked = git_dir.join("worktrees").join("hotfix");
+        fs::create_dir_all(&linked).unwrap();
+        fs::write(linked.join("commondir"), "../..\n").unwrap();
+        let work_tree = root.join("hotfix");
+        fs::create_dir_all(work_tree.join("src")).unwrap();
+        fs::write(
+            work_tree.join(".git"),
+            format!("gitdir: {}\n", linked.display()),
+        )
+        .unwrap();
+        let main_dirs = GitSegment::find_git_dirs(&root.join("repo")).unwrap();
+        let linked_dirs = GitSegment::find_git_dirs(&work_tree.join("src")).unwrap();
+        let main_name = GitSegment::get_worktree_name(&main_dirs);
+        let linked_name = GitSegment::get_worktree_name(&linked_dirs);
+
+        assert_eq!(
+            rebasing,
+            Some(GitRepoState::Rebase {
+                step: Some(3),
+                total: Some(7)
+            })
+        );
+        assert_eq!(rebase_branch.as_deref(), Some("feature"));
+        assert_eq!(cherry_picking, Some(GitRepoState::CherryPick));
+        assert_eq!(linked_dirs.work_tree, work_tree);
+        assert_eq!(main_name, None);
+        assert_eq!(linked_name.as_deref(), Some("hotfix"));
+        assert_eq!(
+            GitSegment::new().format_state(&rebasing.unwrap()),
+            "REBASE 3/7"
+        );
+    }
+
+    #[test]
+    fn status_breakdown_is_opt_in() {
+        let counts = GitSegment::parse_porcelain(
+            "M  staged.rs\n M unstaged.rs\nA  added.rs\n D gone.rs\n?? new.txt\n",
+        );
+        let format = |breakdown: bool, status_format: &str| {
+            let options = HashMap::from([
+                ("status_breakdown".to_string(), serde_json::json!(breakdown)),
+                (
+                    "status_format".to_string(),
+                    serde_json::json!(status_format),
+                ),
+            ]);
+            GitSegment::with_config(&options).format_status(&GitStatus::Dirty, &counts)
+        };
+
+        assert_eq!(format(false, "symbols"), "+1 ~3 -1");
+        assert_eq!(format(false, "count"), "(5 changes)");
+        assert_eq!(format(true, "symbols"), "+2 ~2 ?1");
+        assert_eq!(format(true, "count"), "(2 staged, 2 unstaged, 1 untracked)");
+        assert_eq!(
+            GitSegment::new().format_status(&GitStatus::Conflicts, &counts),
+            "⚠"
+        );
+    }
+
+    #[test]
+    fn diff_stats_cover_all_changes_or_session_files() {
+        let repo = temp_repo("diff");
+        let working_dir = repo.to_str().unwrap();
+        fs::write(repo.join("file.txt"), "one\nTWO\nthree\nfour\n").unwrap();
+        fs::write(repo.join("new.txt"), "a\nb\n").unwrap();
+
+        let transcript = repo.join("transcript.jsonl");
+        let tool_use = |name: &str, path: &Path| {
+            serde_json::json!({"message": {"content": [
+                {"type": "tool_use", "name": name, "input": {"file_path": path}}
+            ]}})
+            .to_string()
+        };
+        fs::write(
+            &transcript,
+            [
+                tool_use("Write", &repo.join("new.txt")),
+                tool_use("Read", &repo.join("file.txt")),
+                tool_use("Edit", Path::new("/elsewhere/file.rs")),
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+
+        let segment = GitSegment::new();
+        let all = segment.get_diff_stats(working_dir, None);
+        let session_files = GitSegment::get_session_files(&transcript, &repo);
+        let session = segment.get_diff_stats(working_dir, Some(&session_files));
+        let untouched = segment.get_diff_stats(working_dir, Some(&[]));
+
+        assert_eq!(all, Some((2, 1)));
+        assert_eq!(session_files, [repo.join("new.txt")]);
+        assert_eq!(session, Some((2, 0)));
+        assert_eq!(untouched, Some((0, 0)));
+    }
+
+    #[test]
+    fn reads_and_formats_last_commit() {
+        let repo = temp_repo("commit");
+        let commit = GitSegment::new().get_last_commit(repo.to_str().unwrap());
+
+        let commit = commit.unwrap();
+        assert_eq!(
+            (commit.subject.as_str(), commit.author.as_str()),
+            ("Initial commit", "Test")
+        );
+        assert!(commit.age_secs < 600);
+
+        let options = HashMap::from([
+            ("show_commit_age".to_string(), serde_json::json!(true)),
+            ("show_commit_subject".to_string(), serde_json::json!(true)),
+            ("show_commit_author".to_string(), serde_json::json!(true)),
+            ("subject_max_length".to_string(), serde_json::json!(8)),
+            ("stale_after_minutes".to_string(), serde_json::json!(60)),
+        ]);
+        let segment = GitSegment::with_config(&options);
+        let old = LastCommit {
+            age_secs: 2 * 86_400,
+            subject: "Initial commit".to_string(),
+            author: "Test".to_string(),
+        };
+        assert_eq!(segment.format_commit(&old), "\u{23f1} 2d Initial… by Test");
+
+        let mut info = GitInfo {
+            branch: "main".to_string(),
+            status: GitStatus::Dirty,
+            status_counts: GitStatusCounts::default(),
+            ahead: 0,
+            behind: 0,
+            sha: None,
+            stash_count: None,
+            tag: None,
+            state: None,
+            worktree: None,
+            diff_stats: None,
+            last_commit: Some(old),
+            upstream: None,
+            base_divergence: None,
+            branch_url: None,
+        };
+        assert!(segment.is_stale(&info));
+        info.status = GitStatus::Clean;
+        assert!(!segment.is_stale(&info));
+    }
+
+    #[test]
+    fn reports_upstream_and_base_branch_divergence() {
+        let repo = temp_repo("upstream");
+        let dir = repo.to_str().unwrap();
+        let segment = GitSegment::new();
+
+        let missing = segment.get_upstream(dir, "main");
+        git(
+            &repo,
+            &["remote", "add", "origin", "https://example.com/repo.git"],
+        );
+        git(&repo, &["config", "branch.main.remote", "origin"]);
+        git(&repo, &["config", "branch.main.merge", "refs/heads/main"]);
+        let gone = segment.get_upstream(dir, "main");
+        git(&repo, &["update-ref", "refs/remotes/origin/main", "HEAD"]);
+        let tracking = segment.get_upstream(dir, "main");
+
+        git(&repo, &["checkout", "-q", "-b", "feature"]);
+        git(
+            &repo,
+            &["commit", "-q", "--allow-empty", "-m", "Feature work"],
+        );
+        let local_base = segment.get_base_divergence(dir, "main");
+        git(&repo, &["branch", "-q", "-D", "main"]);
+        let remote_base = segment.get_base_divergence(dir, "main");
+        let unknown_base = segment.get_base_divergence(dir, "develop");
+
+        assert_eq!(missing, Upstream::None);
+        assert_eq!(gone, Upstream::Gone("origin/main".to_string()));
+        assert_eq!(tracking, Upstream::Tracking("origin/main".to_string()));
+        assert_eq!(local_base, Some((1, 0)));
+        assert_eq!(remote_base, Some((1, 0)));
+        assert_eq!(unknown_base, None);
+        assert_eq!(segment.format_upstream(&gone), "→origin/main gone");
+        assert_eq!(segment.format_base_divergence("main", 1, 0), "main:↑1↓0");
+    }
+
+    #[test]
+    fn other_vcs_emit_the_same_metadata_keys_as_git() {
+        let repo = temp_repo("keys");
+        let input = InputData::for_directory(repo.to_str().unwrap());
+        let git_data = GitSegment::new().collect(&input);
+
+        let jj = vcs::JjInfo {
+            change_id: "kxqpmnzt".to_string(),
+            bookmark: None,
+            status: GitStatus::Clean,
+            status_counts: GitStatusCounts::default(),
+        };
+        let jj_data = GitSegment::new().collect_repository(&jj);
+
+        let git_data = git_data.unwrap();
+        let missing: Vec<&String> = git_data
+            .metadata
+            .keys()
+            .filter(|key| !jj_data.metadata.contains_key(*key))
+            .collect();
+        assert!(missing.is_empty(), "missing for jj: {:?}", missing);
+        assert_eq!(jj_data.metadata["vcs"], "jj");
+        assert_eq!(jj_data.metadata["staged"], "0");
+    }
 }
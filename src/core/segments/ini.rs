@@ -0,0 +1,26 @@
+/// Look up `key` in `[section]` of an INI-style file
+pub(crate) fn ini_value(content: &str, section: &str, key: &str) -> Option<String> {
+    let mut in_section = false;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            in_section = name.trim() == section;
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        if let Some((k, v)) = line.split_once('=') {
+            if k.trim() == key {
+                let value = v.trim();
+                return (!value.is_empty()).then(|| value.to_string());
+            }
+        }
+    }
+
+    None
+}
@@ -0,0 +1,327 @@
+use super::ini::ini_value;
+use super::{Segment, SegmentData};
+use crate::config::models::glob_to_regex;
+use crate::config::{InputData, KubernetesSegmentConfig, SegmentConfig, SegmentId};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// Subset of a kubeconfig file needed to resolve the current context
+#[derive(Debug, Deserialize)]
+struct KubeConfig {
+    #[serde(rename = "current-context")]
+    current_context: Option<String>,
+    contexts: Option<Vec<NamedContext>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NamedContext {
+    name: String,
+    context: Option<KubeContext>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct KubeContext {
+    cluster: Option<String>,
+    namespace: Option<String>,
+    user: Option<String>,
+}
+
+#[derive(Debug)]
+struct KubeInfo {
+    context: String,
+    details: KubeContext,
+    source: PathBuf,
+}
+
+#[derive(Debug)]
+struct AwsInfo {
+    profile: String,
+    region: Option<String>,
+}
+
+#[derive(Debug)]
+struct GcloudInfo {
+    configuration: String,
+    project: Option<String>,
+    account: Option<String>,
+}
+
+pub struct KubernetesSegment {
+    config: KubernetesSegmentConfig,
+}
+
+impl Default for KubernetesSegment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KubernetesSegment {
+    pub fn new() -> Self {
+        Self {
+            config: KubernetesSegmentConfig::default(),
+        }
+    }
+
+    pub fn with_config(options: &HashMap<String, serde_json::Value>) -> Self {
+        Self {
+            config: KubernetesSegmentConfig::from_options(options),
+        }
+    }
+
+    /// Files listed in `$KUBECONFIG`, or `~/.kube/config`
+    fn kubeconfig_paths() -> Vec<PathBuf> {
+        if let Some(value) = env::var_os("KUBECONFIG") {
+            let paths: Vec<PathBuf> = env::split_paths(&value)
+                .filter(|path| !path.as_os_str().is_empty())
+                .collect();
+            if !paths.is_empty() {
+                return paths;
+            }
+        }
+
+        dirs::home_dir()
+            .map(|home| vec![home.join(".kube").join("config")])
+            .unwrap_or_default()
+    }
+
+    /// Resolve the current context the way kubectl merges `$KUBECONFIG`:
+    /// the first file setting a value wins
+    fn read_kube_context() -> Option<KubeInfo> {
+        let configs: Vec<(PathBuf, KubeConfig)> = Self::kubeconfig_paths()
+            .into_iter()
+            .filter_map(|path| {
+                let content = fs::read_to_string(&path).ok()?;
+                let config = serde_yaml_ng::from_str(&content).ok()?;
+                Some((path, config))
+            })
+            .collect();
+
+        let (source, current_context) = configs.iter().find_map(|(path, config)| {
+            config
+                .current_context
+                .as_ref()
+                .filter(|name| !name.is_empty())
+                .map(|name| (path.clone(), name.clone()))
+        })?;
+
+        let details = configs
+            .iter()
+            .filter_map(|(_, config)| config.contexts.as_ref())
+            .flatten()
+            .find(|context| context.name == current_context)
+            .and_then(|context| context.context.clone())
+            .unwrap_or_default();
+
+        Some(KubeInfo {
+            context: current_context,
+            details,
+            source,
+        })
+    }
+
+    /// Active AWS profile from the environment, with its region from `~/.aws/config`
+    fn read_aws_profile() -> Option<AwsInfo> {
+        let profile = ["AWS_PROFILE", "AWS_DEFAULT_PROFILE"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())?;
+
+        let region = ["AWS_REGION", "AWS_DEFAULT_REGION"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .or_else(|| {
+                let config_path = env::var_os("AWS_CONFIG_FILE")
+                    .map(PathBuf::from)
+                    .or_else(|| dirs::home_dir().map(|home| home.join(".aws").join("config")))?;
+                let content = fs::read_to_string(config_path).ok()?;
+                let section = if profile == "default" {
+                    "default".to_string()
+                } else {
+                    format!("profile {}", profile)
+                };
+                ini_value(&content, &section, "region")
+            });
+
+        Some(AwsInfo { profile, region })
+    }
+
+    /// Active gcloud configuration and its core properties
+    fn read_gcloud_config() -> Option<GcloudInfo> {
+        let config_dir = env::var_os("CLOUDSDK_CONFIG")
+            .map(PathBuf::from)
+            .or_else(|| dirs::home_dir().map(|home| home.join(".config").join("gcloud")))?;
+
+        let configuration = env::var("CLOUDSDK_ACTIVE_CONFIG_NAME")
+            .ok()
+            .filter(|name| !name.is_empty())
+            .or_else(|| {
+                fs::read_to_string(config_dir.join("active_config"))
+                    .ok()
+                    .map(|name| name.trim().to_string())
+                    .filter(|name| !name.is_empty())
+            })?;
+
+        let content = fs::read_to_string(
+            config_dir
+                .join("configurations")
+                .join(format!("config_{}", configuration)),
+        )
+        .unwrap_or_default();
+
+        let project = env::var("CLOUDSDK_CORE_PROJECT")
+            .ok()
+            .filter(|project| !project.is_empty())
+            .or_else(|| ini_value(&content, "core", "project"));
+
+        Some(GcloudInfo {
+            configuration,
+            project,
+            account: ini_value(&content, "core", "account"),
+        })
+    }
+
+    fn context_matches(pattern: &str, context: &str) -> bool {
+        glob_to_regex(&pattern.to_lowercase())
+            .map(|re| re.is_match(&context.to_lowercase()))
+            .unwrap_or(false)
+    }
+}
+
+impl Segment for KubernetesSegment {
+    fn collect(&self, _input: &InputData) -> Option<SegmentData> {
+        let kube = Self::read_kube_context();
+        let aws = if self.config.show_aws_profile {
+            Self::read_aws_profile()
+        } else {
+            None
+        };
+        let gcloud = if self.config.show_gcloud {
+            Self::read_gcloud_config()
+        } else {
+            None
+        };
+
+        let mut metadata = HashMap::new();
+        let mut parts = Vec::new();
+
+        if let Some(ref kube) = kube {
+            let namespace = kube
+                .details
+                .namespace
+                .clone()
+                .filter(|ns| !ns.is_empty())
+                .unwrap_or_else(|| "default".to_string());
+
+            metadata.insert("context".to_string(), kube.context.clone());
+            metadata.insert("namespace".to_string(), namespace.clone());
+            metadata.insert(
+                "kubeconfig".to_string(),
+                kube.source.to_string_lossy().to_string(),
+            );
+            if let Some(ref cluster) = kube.details.cluster {
+                metadata.insert("cluster".to_string(), cluster.clone());
+            }
+            if let Some(ref user) = kube.details.user {
+                metadata.insert("user".to_string(), user.clone());
+            }
+
+            let hide_namespace = !self.config.show_namespace
+                || (self.config.hide_default_namespace && namespace == "default");
+            if !hide_namespace {
+                parts.push(namespace);
+            }
+        }
+
+        if let Some(aws) = aws {
+            parts.push(format!("aws:{}", aws.profile));
+            metadata.insert("aws_profile".to_string(), aws.profile);
+            if let Some(region) = aws.region {
+                metadata.insert("aws_region".to_string(), region);
+            }
+        }
+
+        if let Some(gcloud) = gcloud {
+            parts.push(format!(
+                "gcp:{}",
+                gcloud.project.as_deref().unwrap_or(&gcloud.configuration)
+            ));
+            metadata.insert("gcloud_config".to_string(), gcloud.configuration);
+            if let Some(project) = gcloud.project {
+                metadata.insert("gcloud_project".to_string(), project);
+            }
+            if let Some(account) = gcloud.account {
+                metadata.insert("gcloud_account".to_string(), account);
+            }
+        }
+
+        // Without a kube context the first cloud identity becomes the primary text
+        let primary = match kube {
+            Some(kube) => kube.context,
+            None if !parts.is_empty() => parts.remove(0),
+            None => return None,
+        };
+
+        Some(SegmentData {
+            primary,
+            secondary: parts.join(" "),
+            metadata,
+        })
+    }
+
+    fn id(&self) -> SegmentId {
+        SegmentId::Kubernetes
+    }
+
+    fn resolve_style(&self, config: &mut SegmentConfig, data: &SegmentData) {
+        let Some(context) = data.metadata.get("context") else {
+            return;
+        };
+
+        if let Some(rule) = self
+            .config
+            .context_colors
+            .iter()
+            .find(|rule| Self::context_matches(&rule.pattern, context))
+        {
+            config.colors.overlay(&rule.colors);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_kubeconfig_contexts() {
+        let config: KubeConfig = serde_yaml_ng::from_str(
+            r#"
+apiVersion: v1
+kind: Config
+current-context: prod
+contexts:
+  - name: prod
+    context:
+      cluster: prod-cluster
+      namespace: payments
+      user: admin
+clusters: []
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.current_context.as_deref(), Some("prod"));
+        let context = config.contexts.unwrap().remove(0);
+        assert_eq!(context.name, "prod");
+        assert_eq!(
+            context.context.unwrap().namespace.as_deref(),
+            Some("payments")
+        );
+        assert!(KubernetesSegment::context_matches("PROD*", "prod-eu"));
+    }
+}
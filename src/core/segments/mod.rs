@@ -1,19 +1,45 @@
 pub mod cost;
+pub mod custom;
 pub mod directory;
 pub mod git;
+mod ini;
+pub mod kubernetes;
 pub mod model;
 pub mod output_style;
+pub mod plugin;
+mod process;
+pub mod python;
+pub mod registry;
+pub mod script;
 pub mod session;
+pub mod toolchain;
 pub mod update;
 pub mod usage;
+pub mod vcs;
 
-use crate::config::{InputData, SegmentId};
+use crate::config::{InputData, SegmentConfig, SegmentId};
 use std::collections::HashMap;
 
 // New Segment trait for data collection only
 pub trait Segment {
     fn collect(&self, input: &InputData) -> Option<SegmentData>;
     fn id(&self) -> SegmentId;
+
+    /// Adjust icon or colors of this instance based on the collected data
+    fn resolve_style(&self, _config: &mut SegmentConfig, _data: &SegmentData) {}
+
+    /// Segments reading other segments' results are collected after all others
+    fn depends_on_segments(&self) -> bool {
+        false
+    }
+
+    fn collect_with_segments(
+        &self,
+        input: &InputData,
+        _collected: &[(SegmentConfig, SegmentData)],
+    ) -> Option<SegmentData> {
+        self.collect(input)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -25,10 +51,17 @@ pub struct SegmentData {
 
 // Re-export all segment types
 pub use cost::CostSegment;
+pub use custom::CustomSegment;
 pub use directory::DirectorySegment;
 pub use git::GitSegment;
+pub use kubernetes::KubernetesSegment;
 pub use model::ModelSegment;
 pub use output_style::OutputStyleSegment;
+pub use plugin::PluginSegment;
+pub use python::PythonSegment;
+pub use registry::SegmentRegistry;
+pub use script::ScriptSegment;
 pub use session::SessionSegment;
+pub use toolchain::ToolchainSegment;
 pub use update::UpdateSegment;
 pub use usage::UsageSegment;
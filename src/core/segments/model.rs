@@ -22,6 +22,11 @@ impl ModelSegment {
 
 impl Segment for ModelSegment {
     fn collect(&self, input: &InputData) -> Option<SegmentData> {
+        // No model outside a Claude Code session, e.g. in a tmux status line
+        if input.model.id.is_empty() && input.model.display_name.is_empty() {
+            return None;
+        }
+
         let formatted_name = self.format_model_name(&input.model.id, &input.model.display_name);
         
         let (primary, secondary) = match self.config.display_format {
@@ -89,7 +94,6 @@ impl ModelSegment {
         // Apply common abbreviations
         name.replace("claude-", "")
             .replace("3-5-", "3.5-")
-            .replace("4-", "4-")
             .replace("sonnet", "Sonnet")
             .replace("haiku", "Haiku")
             .replace("opus", "Opus")
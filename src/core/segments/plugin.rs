@@ -0,0 +1,301 @@
+use super::process::run_with_timeout;
+use super::{Segment, SegmentData};
+use crate::config::{
+    ColorConfig, IconConfig, InputData, PluginSegmentConfig, SegmentConfig, SegmentId,
+};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+/// Version of the JSON protocol spoken with plugin executables
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// File name prefix identifying plugin executables
+pub const PLUGIN_PREFIX: &str = "ccline-segment-";
+
+/// Argument asking a plugin to describe itself
+pub const HANDSHAKE_ARG: &str = "--ccline-handshake";
+
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// Handshake reply describing a plugin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginManifest {
+    pub protocol: u32,
+    pub name: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+    #[serde(default)]
+    pub icon: Option<IconConfig>,
+    #[serde(default)]
+    pub colors: Option<ColorConfig>,
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+impl PluginManifest {
+    pub fn title(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(&self.name)
+    }
+}
+
+/// Request written to a plugin's stdin for each render
+#[derive(Serialize)]
+struct PluginRequest<'a> {
+    protocol: u32,
+    input: &'a InputData,
+    options: &'a HashMap<String, serde_json::Value>,
+}
+
+/// Reply read from a plugin's stdout; a missing segment hides it
+#[derive(Deserialize)]
+struct PluginResponse {
+    protocol: u32,
+    segment: Option<PluginSegmentData>,
+    #[serde(default)]
+    colors: Option<ColorConfig>,
+}
+
+#[derive(Deserialize)]
+struct PluginSegmentData {
+    primary: String,
+    #[serde(default)]
+    secondary: String,
+    #[serde(default)]
+    metadata: HashMap<String, serde_json::Value>,
+}
+
+/// Directory scanned for plugins (~/.claude/ccline/plugins/)
+pub fn plugins_dir() -> PathBuf {
+    if let Some(home) = dirs::home_dir() {
+        home.join(".claude").join("ccline").join("plugins")
+    } else {
+        PathBuf::from(".claude/ccline/plugins")
+    }
+}
+
+/// Whether a plugin name stays inside the plugins directory
+pub fn is_valid_plugin_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(['/', '\\']) && !name.contains("..")
+}
+
+/// Path of the executable for a plugin name, None for names that would
+/// point outside the plugins directory
+pub fn plugin_path(name: &str) -> Option<PathBuf> {
+    is_valid_plugin_name(name).then(|| plugins_dir().join(format!("{}{}", PLUGIN_PREFIX, name)))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path)
+        .map(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Run the handshake against a plugin executable
+pub fn handshake(path: &Path) -> Result<PluginManifest, String> {
+    let mut command = Command::new(path);
+    command.arg(HANDSHAKE_ARG);
+
+    let output = run_with_timeout(command, "", HANDSHAKE_TIMEOUT)
+        .ok_or_else(|| "handshake failed or timed out".to_string())?;
+    let mut manifest: PluginManifest = serde_json::from_str(output.trim())
+        .map_err(|e| format!("invalid handshake reply: {}", e))?;
+
+    if manifest.protocol != PROTOCOL_VERSION {
+        return Err(format!(
+            "unsupported protocol version {} (expected {})",
+            manifest.protocol, PROTOCOL_VERSION
+        ));
+    }
+
+    // Segments find the executable again by name, so the two must agree
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("");
+    if file_name.strip_prefix(PLUGIN_PREFIX) != Some(manifest.name.as_str()) {
+        return Err(format!(
+            "plugin name \"{}\" doesn't match its file name {}",
+            manifest.name, file_name
+        ));
+    }
+
+    manifest.path = path.to_path_buf();
+    Ok(manifest)
+}
+
+/// Find plugin executables and handshake with each of them.
+/// Results are sorted by file name; failures carry the reason.
+pub fn discover_plugins() -> Vec<(PathBuf, Result<PluginManifest, String>)> {
+    let Ok(entries) = fs::read_dir(plugins_dir()) else {
+        return Vec::new();
+    };
+
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(PLUGIN_PREFIX))
+        })
+        .filter(|path| is_executable(path))
+        .collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let manifest = handshake(&path);
+            (path, manifest)
+        })
+        .collect()
+}
+
+pub struct PluginSegment {
+    config: PluginSegmentConfig,
+    options: HashMap<String, serde_json::Value>,
+    suggested_colors: RefCell<Option<ColorConfig>>,
+}
+
+impl PluginSegment {
+    pub fn with_config(options: &HashMap<String, serde_json::Value>) -> Self {
+        Self {
+            config: PluginSegmentConfig::from_options(options),
+            options: options.clone(),
+            suggested_colors: RefCell::new(None),
+        }
+    }
+}
+
+impl Segment for PluginSegment {
+    fn collect(&self, input: &InputData) -> Option<SegmentData> {
+        let request = serde_json::to_string(&PluginRequest {
+            protocol: PROTOCOL_VERSION,
+            input,
+            options: &self.options,
+        })
+        .ok()?;
+
+        let mut command = Command::new(plugin_path(&self.config.plugin)?);
+        if !input.workspace.current_dir.is_empty() {
+            command.current_dir(&input.workspace.current_dir);
+        }
+
+        let output = run_with_timeout(
+            command,
+            &request,
+            Duration::from_millis(self.config.timeout_ms),
+        )?;
+        let response: PluginResponse = serde_json::from_str(output.trim()).ok()?;
+        if response.protocol != PROTOCOL_VERSION {
+            return None;
+        }
+
+        let segment = response.segment?;
+        *self.suggested_colors.borrow_mut() = response.colors;
+
+        let mut metadata: HashMap<String, String> = segment
+            .metadata
+            .into_iter()
+            .map(|(key, value)| match value {
+                serde_json::Value::String(s) => (key, s),
+                other => (key, other.to_string()),
+            })
+            .collect();
+        metadata.insert("plugin".to_string(), self.config.plugin.clone());
+
+        Some(SegmentData {
+            primary: segment.primary,
+            secondary: segment.secondary,
+            metadata,
+        })
+    }
+
+    fn id(&self) -> SegmentId {
+        SegmentId::Plugin
+    }
+
+    fn resolve_style(&self, config: &mut SegmentConfig, _data: &SegmentData) {
+        // Suggested colors only fill in what the user left unset
+        if let Some(suggested) = self.suggested_colors.borrow().as_ref() {
+            let colors = &mut config.colors;
+            if colors.icon.is_none() {
+                colors.icon = suggested.icon.clone();
+            }
+            if colors.text.is_none() {
+                colors.text = suggested.text.clone();
+            }
+            if colors.background.is_none() {
+                colors.background = suggested.background.clone();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_dir::TestDir;
+
+    #[test]
+    fn rejects_plugin_names_leaving_the_plugins_directory() {
+        assert!(plugin_path("weather").is_some());
+        assert!(plugin_path("weather-v2.1").is_some());
+        for name in ["", "../../tmp/x", "a/b", "a\\b", ".."] {
+            assert!(plugin_path(name).is_none(), "{}", name);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn handshakes_with_plugin_executables() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TestDir::new("plugin");
+        let write_plugin = |name: &str, reply: &str| {
+            let path = dir.join(name);
+            fs::write(&path, format!("#!/bin/sh\necho '{}'\n", reply)).unwrap();
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+            path
+        };
+        let current = write_plugin(
+            "ccline-segment-weather",
+            r#"{"protocol": 1, "name": "weather", "display_name": "Weather"}"#,
+        );
+        let future = write_plugin(
+            "ccline-segment-future",
+            r#"{"protocol": 2, "name": "future"}"#,
+        );
+        let renamed = write_plugin(
+            "ccline-segment-forecast",
+            r#"{"protocol": 1, "name": "weather"}"#,
+        );
+
+        let manifest = handshake(&current);
+        let unsupported = handshake(&future);
+
+        let manifest = manifest.unwrap();
+        assert_eq!(manifest.title(), "Weather");
+        assert_eq!(manifest.path, current);
+        assert!(unsupported.unwrap_err().contains("protocol version 2"));
+        assert!(handshake(&renamed)
+            .unwrap_err()
+            .contains("doesn't match its file name"));
+    }
+}
@@ -0,0 +1,93 @@
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Run a command with `input` on stdin and return its stdout.
+/// Returns None on spawn failure, non-zero exit or timeout.
+pub(crate) fn run_with_timeout(
+    mut command: Command,
+    input: &str,
+    timeout: Duration,
+) -> Option<String> {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    // Commands that ignore stdin may exit before reading it, so write errors are fine
+    if let Some(mut stdin) = child.stdin.take() {
+        let input = input.to_string();
+        thread::spawn(move || {
+            let _ = stdin.write_all(input.as_bytes());
+        });
+    }
+
+    // Drain stdout concurrently so a chatty command cannot block on a full pipe
+    let mut stdout = child.stdout.take()?;
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut output = String::new();
+        let _ = stdout.read_to_string(&mut output);
+        let _ = sender.send(output);
+    });
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(5)),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    };
+
+    // A background process can keep stdout open after the command exits
+    let output = receiver
+        .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+        .ok()?;
+    if status.success() {
+        Some(output)
+    } else {
+        None
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn sh(script: &str) -> Command {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(script);
+        command
+    }
+
+    #[test]
+    fn returns_output_only_for_successful_commands() {
+        let timeout = Duration::from_secs(5);
+        assert_eq!(
+            run_with_timeout(sh("cat; echo !"), "ping", timeout).as_deref(),
+            Some("ping!\n")
+        );
+        assert!(run_with_timeout(sh("echo partial; exit 1"), "", timeout).is_none());
+        assert!(run_with_timeout(Command::new("/nonexistent/ccline"), "", timeout).is_none());
+    }
+
+    #[test]
+    fn gives_up_at_the_deadline() {
+        let timeout = Duration::from_millis(200);
+
+        let started = Instant::now();
+        assert!(run_with_timeout(sh("sleep 5"), "", timeout).is_none());
+        // The background sleep keeps stdout open after the shell exits
+        assert!(run_with_timeout(sh("sleep 5 & echo started"), "", timeout).is_none());
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+}
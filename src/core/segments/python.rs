@@ -0,0 +1,274 @@
+use super::{Segment, SegmentData};
+use crate::config::{InputData, PythonSegmentConfig, SegmentId};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Directory names that say nothing about the project they belong to
+const GENERIC_VENV_NAMES: [&str; 4] = [".venv", "venv", ".env", "env"];
+
+#[derive(Debug)]
+struct PythonEnv {
+    name: String,
+    kind: &'static str,
+    version: Option<(String, &'static str)>,
+}
+
+pub struct PythonSegment {
+    config: PythonSegmentConfig,
+}
+
+impl Default for PythonSegment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PythonSegment {
+    pub fn new() -> Self {
+        Self {
+            config: PythonSegmentConfig::default(),
+        }
+    }
+
+    pub fn with_config(options: &HashMap<String, serde_json::Value>) -> Self {
+        Self {
+            config: PythonSegmentConfig::from_options(options),
+        }
+    }
+
+    /// Detect the active virtualenv or conda environment
+    fn detect_active_env(&self) -> Option<PythonEnv> {
+        if let Ok(venv_path) = env::var("VIRTUAL_ENV") {
+            if !venv_path.is_empty() {
+                let path = Path::new(&venv_path);
+                return Some(PythonEnv {
+                    name: Self::venv_name(path),
+                    kind: "venv",
+                    version: Self::read_pyvenv_cfg(path).map(|v| (v, "pyvenv.cfg")),
+                });
+            }
+        }
+
+        if let Ok(conda_env) = env::var("CONDA_DEFAULT_ENV") {
+            if conda_env.is_empty() || (self.config.ignore_conda_base && conda_env == "base") {
+                return None;
+            }
+            let version = env::var("CONDA_PREFIX")
+                .ok()
+                .and_then(|prefix| Self::read_conda_meta(Path::new(&prefix)))
+                .map(|v| (v, "conda-meta"));
+            return Some(PythonEnv {
+                name: conda_env,
+                kind: "conda",
+                version,
+            });
+        }
+
+        None
+    }
+
+    /// Name of a virtualenv, preferring the prompt set by `activate`
+    fn venv_name(path: &Path) -> String {
+        if let Ok(prompt) = env::var("VIRTUAL_ENV_PROMPT") {
+            let prompt = prompt.trim().trim_start_matches('(').trim_end_matches(')');
+            if !prompt.is_empty() {
+                return prompt.to_string();
+            }
+        }
+
+        let dir_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+
+        // `.venv` inside a project: the project directory is the useful name
+        if GENERIC_VENV_NAMES.contains(&dir_name) {
+            if let Some(project) = path
+                .parent()
+                .and_then(|p| p.file_name())
+                .and_then(|name| name.to_str())
+            {
+                return project.to_string();
+            }
+        }
+
+        dir_name.to_string()
+    }
+
+    /// Read the interpreter version recorded in `<venv>/pyvenv.cfg`
+    fn read_pyvenv_cfg(venv_path: &Path) -> Option<String> {
+        let content = fs::read_to_string(venv_path.join("pyvenv.cfg")).ok()?;
+
+        for line in content.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let key = key.trim();
+            if key == "version" || key == "version_info" {
+                // version_info looks like 3.12.1.final.0
+                let version: Vec<&str> = value.trim().split('.').take(3).collect();
+                return Some(version.join("."));
+            }
+        }
+
+        None
+    }
+
+    /// Find the interpreter version from `<prefix>/conda-meta/python-<version>-<build>.json`
+    fn read_conda_meta(prefix: &Path) -> Option<String> {
+        let entries = fs::read_dir(prefix.join("conda-meta")).ok()?;
+
+        for entry in entries.flatten() {
+            let file_name = entry.file_name();
+            let Some(name) = file_name.to_str() else {
+                continue;
+            };
+            if let Some(rest) = name.strip_prefix("python-") {
+                let version = rest.split('-').next().unwrap_or_default();
+                if version.starts_with(|c: char| c.is_ascii_digit()) {
+                    return Some(version.to_string());
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Read the version pinned by the project in the working directory
+    pub(super) fn read_project_version(dir: &Path) -> Option<(String, &'static str)> {
+        if let Ok(content) = fs::read_to_string(dir.join(".python-version")) {
+            if let Some(version) = content.lines().map(str::trim).find(|l| !l.is_empty()) {
+                return Some((version.to_string(), ".python-version"));
+            }
+        }
+
+        let content = fs::read_to_string(dir.join("pyproject.toml")).ok()?;
+        let pyproject: toml::Value = toml::from_str(&content).ok()?;
+
+        let requires = pyproject
+            .get("project")
+            .and_then(|project| project.get("requires-python"))
+            .or_else(|| {
+                pyproject
+                    .get("tool")
+                    .and_then(|tool| tool.get("poetry"))
+                    .and_then(|poetry| poetry.get("dependencies"))
+                    .and_then(|deps| deps.get("python"))
+            })
+            .and_then(|v| v.as_str())?;
+
+        Some((requires.trim().to_string(), "pyproject.toml"))
+    }
+
+    /// Text for a version; `requires-python` is a constraint, not the interpreter
+    fn display_version(version: &str, source: &str) -> String {
+        if source == "pyproject.toml" {
+            format!("requires {}", version)
+        } else {
+            version.to_string()
+        }
+    }
+}
+
+impl Segment for PythonSegment {
+    fn collect(&self, input: &InputData) -> Option<SegmentData> {
+        let project_dir = Path::new(&input.workspace.current_dir);
+        let active_env = self.detect_active_env();
+
+        let (primary, version) = match active_env {
+            Some(ref python_env) => {
+                let version = python_env
+                    .version
+                    .clone()
+                    .or_else(|| Self::read_project_version(project_dir));
+                (python_env.name.clone(), version)
+            }
+            None if self.config.show_project_version => {
+                let version = Self::read_project_version(project_dir)?;
+                (Self::display_version(&version.0, version.1), Some(version))
+            }
+            None => return None,
+        };
+
+        // Without an active env the version already is the primary text
+        let secondary = match (&active_env, &version) {
+            (Some(_), Some((version, source))) if self.config.show_version => {
+                Self::display_version(version, source)
+            }
+            _ => String::new(),
+        };
+
+        let mut metadata = HashMap::new();
+        metadata.insert(
+            "env_type".to_string(),
+            active_env
+                .as_ref()
+                .map(|e| e.kind)
+                .unwrap_or("none")
+                .to_string(),
+        );
+        if let Some(ref python_env) = active_env {
+            metadata.insert("env_name".to_string(), python_env.name.clone());
+        }
+        if let Some((version, source)) = version {
+            metadata.insert("version".to_string(), version);
+            metadata.insert("version_source".to_string(), source.to_string());
+        }
+
+        Some(SegmentData {
+            primary,
+            secondary,
+            metadata,
+        })
+    }
+
+    fn id(&self) -> SegmentId {
+        SegmentId::Python
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_dir::TestDir;
+
+    #[test]
+    fn reads_versions_and_marks_requires_python_as_constraint() {
+        let dir = TestDir::new("python");
+        fs::create_dir_all(dir.join("conda-meta")).unwrap();
+        fs::write(
+            dir.join("pyvenv.cfg"),
+            "home = /usr/bin\nversion_info = 3.12.1.final.0\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("conda-meta").join("python-3.11.9-h1234_0.json"),
+            "{}",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("pyproject.toml"),
+            "[project]\nname = \"demo\"\nrequires-python = \">=3.10\"\n",
+        )
+        .unwrap();
+
+        let pyvenv = PythonSegment::read_pyvenv_cfg(&dir);
+        let conda = PythonSegment::read_conda_meta(&dir);
+        let requires = PythonSegment::read_project_version(&dir);
+        fs::write(dir.join(".python-version"), "\n3.12.4\n").unwrap();
+        let pinned = PythonSegment::read_project_version(&dir);
+
+        assert_eq!(pyvenv.as_deref(), Some("3.12.1"));
+        assert_eq!(conda.as_deref(), Some("3.11.9"));
+        let (version, source) = requires.unwrap();
+        assert_eq!((version.as_str(), source), (">=3.10", "pyproject.toml"));
+        assert_eq!(
+            PythonSegment::display_version(&version, source),
+            "requires >=3.10"
+        );
+        let (version, source) = pinned.unwrap();
+        assert_eq!(PythonSegment::display_version(&version, source), "3.12.4");
+    }
+}
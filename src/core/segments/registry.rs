@@ -0,0 +1,193 @@
+use super::plugin::{self, PluginManifest};
+use super::*;
+use crate::config::{Config, SegmentConfig, SegmentId};
+
+type SegmentFactory = fn(&SegmentConfig) -> Box<dyn Segment>;
+
+/// A segment type known to ccline
+pub struct SegmentDescriptor {
+    pub id: SegmentId,
+    pub name: &'static str,
+    factory: SegmentFactory,
+}
+
+/// Built-in segments, in the order they are offered to users
+pub const BUILTIN_SEGMENTS: &[SegmentDescriptor] = &[
+    SegmentDescriptor {
+        id: SegmentId::Model,
+        name: "Model",
+        factory: |config| Box::new(ModelSegment::with_config(&config.options)),
+    },
+    SegmentDescriptor {
+        id: SegmentId::Directory,
+        name: "Directory",
+        factory: |config| Box::new(DirectorySegment::new().with_config(&config.options)),
+    },
+    SegmentDescriptor {
+        id: SegmentId::Git,
+        name: "Git",
+        factory: |config| Box::new(GitSegment::with_config(&config.options)),
+    },
+    SegmentDescriptor {
+        id: SegmentId::Usage,
+        name: "Usage",
+        factory: |config| Box::new(UsageSegment::new().with_config(&config.options)),
+    },
+    SegmentDescriptor {
+        id: SegmentId::Cost,
+        name: "Cost",
+        factory: |config| Box::new(CostSegment::with_config(&config.options)),
+    },
+    SegmentDescriptor {
+        id: SegmentId::Session,
+        name: "Session",
+        factory: |config| Box::new(SessionSegment::with_config(&config.options)),
+    },
+    SegmentDescriptor {
+        id: SegmentId::OutputStyle,
+        name: "Output Style",
+        factory: |config| Box::new(OutputStyleSegment::with_config(&config.options)),
+    },
+    SegmentDescriptor {
+        id: SegmentId::Update,
+        name: "Update",
+        factory: |config| Box::new(UpdateSegment::with_config(&config.options)),
+    },
+    SegmentDescriptor {
+        id: SegmentId::Python,
+        name: "Python",
+        factory: |config| Box::new(PythonSegment::with_config(&config.options)),
+    },
+    SegmentDescriptor {
+        id: SegmentId::Toolchain,
+        name: "Toolchain",
+        factory: |config| Box::new(ToolchainSegment::with_config(&config.options)),
+    },
+    SegmentDescriptor {
+        id: SegmentId::Kubernetes,
+        name: "Kubernetes",
+        factory: |config| Box::new(KubernetesSegment::with_config(&config.options)),
+    },
+    SegmentDescriptor {
+        id: SegmentId::Custom,
+        name: "Custom",
+        factory: |config| Box::new(CustomSegment::with_config(&config.options)),
+    },
+    SegmentDescriptor {
+        id: SegmentId::Plugin,
+        name: "Plugin",
+        factory: |config| Box::new(PluginSegment::with_config(&config.options)),
+    },
+    SegmentDescriptor {
+        id: SegmentId::Script,
+        name: "Script",
+        factory: |config| Box::new(ScriptSegment::with_config(&config.options)),
+    },
+];
+
+/// Maps segment configs to segment implementations, built-in or plugin
+#[derive(Default)]
+pub struct SegmentRegistry {
+    plugins: Vec<PluginManifest>,
+}
+
+impl SegmentRegistry {
+    /// Registry of built-in segments; plugins are resolved by name when collected
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry that also knows the plugins installed in the plugins directory
+    pub fn discover() -> Self {
+        let plugins = plugin::discover_plugins()
+            .into_iter()
+            .filter_map(|(_, manifest)| manifest.ok())
+            .collect();
+        Self { plugins }
+    }
+
+    pub fn descriptor(id: SegmentId) -> &'static SegmentDescriptor {
+        BUILTIN_SEGMENTS
+            .iter()
+            .find(|descriptor| descriptor.id == id)
+            .expect("every SegmentId has a descriptor")
+    }
+
+    pub fn create(&self, config: &SegmentConfig) -> Box<dyn Segment> {
+        (Self::descriptor(config.id).factory)(config)
+    }
+
+    /// Installed plugins that passed the handshake
+    pub fn plugins(&self) -> &[PluginManifest] {
+        &self.plugins
+    }
+
+    /// Installed plugins not yet referenced by any segment in `config`
+    pub fn available_plugins(&self, config: &Config) -> Vec<&PluginManifest> {
+        self.plugins
+            .iter()
+            .filter(|manifest| {
+                !config
+                    .segments
+                    .iter()
+                    .any(|segment| Self::plugin_name(segment) == Some(manifest.name.as_str()))
+            })
+            .collect()
+    }
+
+    /// Human readable name for a configured segment, including its instance name
+    pub fn display_name(&self, config: &SegmentConfig) -> String {
+        let type_name = self.type_name(config);
+        match config.name {
+            Some(ref name) => format!("{} ({})", type_name, name),
+            None => type_name,
+        }
+    }
+
+    fn type_name(&self, config: &SegmentConfig) -> String {
+        match Self::plugin_name(config) {
+            Some(name) => self
+                .plugins
+                .iter()
+                .find(|manifest| manifest.name == name)
+                .map(|manifest| manifest.title().to_string())
+                .unwrap_or_else(|| name.to_string()),
+            None => Self::descriptor(config.id).name.to_string(),
+        }
+    }
+
+    /// Segment config that adds a plugin, using the plugin's suggested icon and colors
+    pub fn plugin_segment_config(manifest: &PluginManifest) -> SegmentConfig {
+        let mut options = std::collections::HashMap::new();
+        options.insert(
+            "plugin".to_string(),
+            serde_json::Value::String(manifest.name.clone()),
+        );
+
+        SegmentConfig {
+            id: SegmentId::Plugin,
+            name: None,
+            show_when: None,
+            hide_when: None,
+            enabled: true,
+            icon: manifest.icon.clone().unwrap_or(crate::config::IconConfig {
+                plain: "🧩".to_string(),
+                nerd_font: "\u{f12e}".to_string(),
+            }),
+            colors: manifest.colors.clone().unwrap_or(crate::config::ColorConfig {
+                icon: None,
+                text: None,
+                background: None,
+            }),
+            styles: Default::default(),
+            options,
+        }
+    }
+
+    fn plugin_name(config: &SegmentConfig) -> Option<&str> {
+        if config.id != SegmentId::Plugin {
+            return None;
+        }
+        config.options.get("plugin").and_then(|v| v.as_str())
+    }
+}
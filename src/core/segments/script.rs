@@ -0,0 +1,349 @@
+use super::{Segment, SegmentData};
+use crate::config::{ColorConfig, InputData, ScriptSegmentConfig, SegmentConfig, SegmentId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+pub struct ScriptSegment {
+    config: ScriptSegmentConfig,
+    chosen_colors: RefCell<Option<ColorConfig>>,
+}
+
+impl ScriptSegment {
+    pub fn with_config(options: &HashMap<String, serde_json::Value>) -> Self {
+        Self {
+            config: ScriptSegmentConfig::from_options(options),
+            chosen_colors: RefCell::new(None),
+        }
+    }
+
+    /// Check that the script can be loaded and compiled
+    pub fn validate(
+        options: &HashMap<String, serde_json::Value>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let segment = Self::with_config(options);
+        let source = segment.load_source()?;
+        engine::compile(&segment.config, &source)
+    }
+
+    /// Inline `script` takes precedence over `script_file`
+    fn load_source(&self) -> Result<String, Box<dyn std::error::Error>> {
+        if !self.config.script.trim().is_empty() {
+            return Ok(self.config.script.clone());
+        }
+
+        let Some(ref file) = self.config.script_file else {
+            return Err("Script segment needs a \"script\" or \"script_file\" option".into());
+        };
+        let path = Self::resolve_script_path(file);
+        std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read script {}: {}", path.display(), e).into())
+    }
+
+    /// `~/` expands to the home directory; relative paths are relative to ~/.claude/ccline/
+    fn resolve_script_path(file: &str) -> PathBuf {
+        let home = dirs::home_dir().unwrap_or_default();
+        if let Some(rest) = file.strip_prefix("~/") {
+            return home.join(rest);
+        }
+
+        let path = PathBuf::from(file);
+        if path.is_absolute() {
+            path
+        } else {
+            home.join(".claude").join("ccline").join(path)
+        }
+    }
+}
+
+impl Segment for ScriptSegment {
+    fn collect(&self, input: &InputData) -> Option<SegmentData> {
+        self.collect_with_segments(input, &[])
+    }
+
+    fn id(&self) -> SegmentId {
+        SegmentId::Script
+    }
+
+    fn depends_on_segments(&self) -> bool {
+        true
+    }
+
+    fn collect_with_segments(
+        &self,
+        input: &InputData,
+        collected: &[(SegmentConfig, SegmentData)],
+    ) -> Option<SegmentData> {
+        let source = self.load_source().ok()?;
+        let output = engine::run(&self.config, &source, input, collected)?;
+        *self.chosen_colors.borrow_mut() = Some(output.colors);
+
+        Some(SegmentData {
+            primary: output.text,
+            secondary: output.secondary,
+            metadata: HashMap::new(),
+        })
+    }
+
+    fn resolve_style(&self, config: &mut SegmentConfig, _data: &SegmentData) {
+        if let Some(colors) = self.chosen_colors.borrow().as_ref() {
+            config.colors.overlay(colors);
+        }
+    }
+}
+
+/// Text and colors returned by a script
+struct ScriptOutput {
+    text: String,
+    secondary: String,
+    colors: ColorConfig,
+}
+
+#[cfg(feature = "scripting")]
+mod engine {
+    use super::ScriptOutput;
+    use crate::config::{AnsiColor, ColorConfig, InputData, ScriptSegmentConfig, SegmentConfig};
+    use crate::core::segments::SegmentData;
+    use rhai::{Dynamic, Engine, Map, Scope};
+
+    fn new_engine(config: &ScriptSegmentConfig) -> Engine {
+        let mut engine = Engine::new();
+        engine.set_max_operations(config.max_operations);
+        engine
+    }
+
+    pub(super) fn compile(
+        config: &ScriptSegmentConfig,
+        source: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        new_engine(config)
+            .compile(source)
+            .map(|_| ())
+            .map_err(|e| format!("Script error: {}", e).into())
+    }
+
+    pub(super) fn run(
+        config: &ScriptSegmentConfig,
+        source: &str,
+        input: &InputData,
+        collected: &[(SegmentConfig, SegmentData)],
+    ) -> Option<ScriptOutput> {
+        let engine = new_engine(config);
+
+        let mut segments = Map::new();
+        for (segment_config, data) in collected {
+            // Repeated unnamed segments share a key; later ones get `#2`, `#3`, ...
+            let mut key = segment_config.key();
+            let mut instance = 1;
+            while segments.contains_key(key.as_str()) {
+                instance += 1;
+                key = format!("{}#{}", segment_config.key(), instance);
+            }
+            let metadata: Map = data
+                .metadata
+                .iter()
+                .map(|(k, v)| (k.as_str().into(), Dynamic::from(v.clone())))
+                .collect();
+
+            let mut entry = Map::new();
+            entry.insert("primary".into(), Dynamic::from(data.primary.clone()));
+            entry.insert("secondary".into(), Dynamic::from(data.secondary.clone()));
+            entry.insert("metadata".into(), Dynamic::from_map(metadata));
+            segments.insert(key.into(), Dynamic::from_map(entry));
+        }
+
+        let mut scope = Scope::new();
+        scope.push_dynamic("input", rhai::serde::to_dynamic(input).ok()?);
+        scope.push_dynamic("segments", Dynamic::from_map(segments));
+
+        let result: Dynamic = engine.eval_with_scope(&mut scope, source).ok()?;
+        into_output(result)
+    }
+
+    /// Accept either a plain string or a map with text and color fields
+    fn into_output(result: Dynamic) -> Option<ScriptOutput> {
+        if result.is_unit() {
+            return None;
+        }
+
+        let mut output = ScriptOutput {
+            text: String::new(),
+            secondary: String::new(),
+            colors: ColorConfig {
+                icon: None,
+                text: None,
+                background: None,
+            },
+        };
+
+        if let Some(map) = result.clone().try_cast::<Map>() {
+            let get_string = |key: &str| {
+                map.get(key)
+                    .filter(|v| !v.is_unit())
+                    .map(|v| v.to_string())
+                    .unwrap_or_default()
+            };
+            output.text = get_string("text");
+            output.secondary = get_string("secondary");
+            output.colors.text = map.get("color").and_then(parse_color);
+            output.colors.icon = map
+                .get("icon_color")
+                .or_else(|| map.get("color"))
+                .and_then(parse_color);
+            output.colors.background = map.get("background").and_then(parse_color);
+        } else {
+            output.text = result.to_string();
+        }
+
+        if output.text.is_empty() {
+            None
+        } else {
+            Some(output)
+        }
+    }
+
+    /// Colors may be names ("red", "bright_blue"), "#rrggbb", a 256-color index,
+    /// or the same maps used in config files (#{ c16: 1 })
+    fn parse_color(value: &Dynamic) -> Option<AnsiColor> {
+        if let Some(index) = value.clone().try_cast::<i64>() {
+            return u8::try_from(index)
+                .ok()
+                .map(|c256| AnsiColor::Color256 { c256 });
+        }
+
+        if value.is_map() {
+            return rhai::serde::from_dynamic(value).ok();
+        }
+
+        let name = value.clone().into_string().ok()?.to_lowercase();
+        if let Some(hex) = name.strip_prefix('#') {
+            if hex.len() != 6 {
+                return None;
+            }
+            let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+            return Some(AnsiColor::Rgb {
+                r: channel(0)?,
+                g: channel(2)?,
+                b: channel(4)?,
+            });
+        }
+
+        const NAMES: [&str; 8] = [
+            "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+        ];
+        let (base, offset) = match name.strip_prefix("bright_") {
+            Some(base) => (base, 8),
+            None => (name.as_str(), 0),
+        };
+        NAMES
+            .iter()
+            .position(|n| *n == base)
+            .map(|i| AnsiColor::Color16 {
+                c16: i as u8 + offset,
+            })
+    }
+}
+
+#[cfg(not(feature = "scripting"))]
+mod engine {
+    use super::ScriptOutput;
+    use crate::config::{InputData, ScriptSegmentConfig, SegmentConfig};
+    use crate::core::segments::SegmentData;
+
+    pub(super) fn compile(
+        _config: &ScriptSegmentConfig,
+        _source: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        Err("Script segments require ccline built with the `scripting` feature".into())
+    }
+
+    pub(super) fn run(
+        _config: &ScriptSegmentConfig,
+        _source: &str,
+        _input: &InputData,
+        _collected: &[(SegmentConfig, SegmentData)],
+    ) -> Option<ScriptOutput> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn options(script: &str) -> HashMap<String, serde_json::Value> {
+        HashMap::from([("script".to_string(), json!(script))])
+    }
+
+    #[test]
+    fn validate_requires_a_script() {
+        let error = ScriptSegment::validate(&HashMap::new()).unwrap_err();
+        assert!(error.to_string().contains("\"script\" or \"script_file\""));
+    }
+
+    #[cfg(not(feature = "scripting"))]
+    #[test]
+    fn scripts_need_the_scripting_feature() {
+        let error = ScriptSegment::validate(&options("\"hi\"")).unwrap_err();
+        assert!(error.to_string().contains("`scripting` feature"));
+    }
+
+    #[cfg(feature = "scripting")]
+    #[test]
+    fn script_reads_collected_segments_and_sets_colors() {
+        use crate::config::{AnsiColor, Config};
+
+        let git = Config::default()
+            .segments
+            .into_iter()
+            .find(|segment| segment.id == SegmentId::Git)
+            .unwrap();
+        let data = |primary: &str| SegmentData {
+            primary: primary.to_string(),
+            secondary: String::new(),
+            metadata: HashMap::from([("dirty".to_string(), "true".to_string())]),
+        };
+        let mut custom = git.clone();
+        custom.id = SegmentId::Custom;
+        let collected = [
+            (git.clone(), data("main")),
+            (custom.clone(), data("first")),
+            (custom, data("second")),
+        ];
+        let segment = ScriptSegment::with_config(&options(
+            r##"
+            let git = segments["git"];
+            #{ text: git.primary + "*" + segments["custom"].primary + segments["custom#2"].primary,
+               secondary: input.workspace.current_dir,
+               color: "bright_red", background: "#102030" }
+            "##,
+        ));
+
+        let input = InputData::for_directory("/work");
+        let data = segment.collect_with_segments(&input, &collected).unwrap();
+        assert_eq!(
+            (data.primary.as_str(), data.secondary.as_str()),
+            ("main*firstsecond", "/work")
+        );
+
+        let mut config = git;
+        segment.resolve_style(&mut config, &data);
+        assert!(matches!(
+            config.colors.text,
+            Some(AnsiColor::Color16 { c16: 9 })
+        ));
+        assert!(matches!(
+            config.colors.background,
+            Some(AnsiColor::Rgb {
+                r: 16,
+                g: 32,
+                b: 48
+            })
+        ));
+
+        assert!(ScriptSegment::validate(&options("let x = ;")).is_err());
+        let hidden = ScriptSegment::with_config(&options("()"));
+        assert!(hidden.collect(&input).is_none());
+    }
+}
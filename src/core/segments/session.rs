@@ -33,7 +33,7 @@ impl SessionSegment {
             format!("{}ms", ms)
         } else if ms < 60_000 {
             let seconds = ms / 1000;
-            if self.config.show_milliseconds && ms % 1000 != 0 {
+            if self.config.show_milliseconds && !ms.is_multiple_of(1000) {
                 format!("{}.{:01}s", seconds, (ms % 1000) / 100)
             } else {
                 format!("{}s", seconds)
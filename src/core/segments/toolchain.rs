@@ -0,0 +1,397 @@
+use super::python::PythonSegment;
+use super::{Segment, SegmentData};
+use crate::config::{InputData, SegmentConfig, SegmentId, ToolchainSegmentConfig};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Icon for a detected project language
+#[derive(Debug, Clone, Copy)]
+pub struct LanguageIcon {
+    pub language: &'static str,
+    pub name: &'static str,
+    pub plain: &'static str,
+    pub nerd_font: &'static str,
+}
+
+/// Language icons shared by the Toolchain segment and the TUI icon selector
+pub const LANGUAGE_ICONS: &[LanguageIcon] = &[
+    LanguageIcon {
+        language: "rust",
+        name: "Rust",
+        plain: "🦀",
+        nerd_font: "\u{e7a8}",
+    },
+    LanguageIcon {
+        language: "node",
+        name: "Node.js",
+        plain: "⬢",
+        nerd_font: "\u{e718}",
+    },
+    LanguageIcon {
+        language: "go",
+        name: "Go",
+        plain: "🐹",
+        nerd_font: "\u{e627}",
+    },
+    LanguageIcon {
+        language: "python",
+        name: "Python",
+        plain: "🐍",
+        nerd_font: "\u{e73c}",
+    },
+    LanguageIcon {
+        language: "java",
+        name: "Java",
+        plain: "☕",
+        nerd_font: "\u{e738}",
+    },
+];
+
+/// Marker files identifying a project type, in detection order
+const PROJECT_MARKERS: [(&str, &str); 5] = [
+    ("Cargo.toml", "rust"),
+    ("package.json", "node"),
+    ("go.mod", "go"),
+    ("pyproject.toml", "python"),
+    ("pom.xml", "java"),
+];
+
+pub fn language_icon(language: &str) -> Option<&'static LanguageIcon> {
+    LANGUAGE_ICONS.iter().find(|icon| icon.language == language)
+}
+
+#[derive(Debug)]
+struct Project {
+    root: PathBuf,
+    marker: &'static str,
+    language: &'static str,
+}
+
+pub struct ToolchainSegment {
+    config: ToolchainSegmentConfig,
+}
+
+impl Default for ToolchainSegment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ToolchainSegment {
+    pub fn new() -> Self {
+        Self {
+            config: ToolchainSegmentConfig::default(),
+        }
+    }
+
+    pub fn with_config(options: &HashMap<String, serde_json::Value>) -> Self {
+        Self {
+            config: ToolchainSegmentConfig::from_options(options),
+        }
+    }
+
+    /// Find the nearest directory containing a project marker file
+    fn detect_project(&self, working_dir: &Path) -> Option<Project> {
+        for dir in working_dir.ancestors() {
+            for (marker, language) in PROJECT_MARKERS {
+                if dir.join(marker).is_file() {
+                    return Some(Project {
+                        root: dir.to_path_buf(),
+                        marker,
+                        language,
+                    });
+                }
+            }
+
+            if !self.config.search_ancestors {
+                break;
+            }
+        }
+
+        None
+    }
+
+    /// Read the pinned toolchain version, returning (version, source file)
+    fn detect_version(project: &Project) -> Option<(String, String)> {
+        let root = project.root.as_path();
+
+        let pinned = match project.language {
+            "rust" => Self::rust_version(root),
+            "node" => Self::node_version(root),
+            "go" => Self::go_version(root),
+            "python" => PythonSegment::read_project_version(root)
+                .map(|(version, source)| (version, source.to_string())),
+            "java" => Self::java_version(root),
+            _ => None,
+        };
+
+        pinned.or_else(|| Self::tool_versions(root, project.language))
+    }
+
+    fn rust_version(root: &Path) -> Option<(String, String)> {
+        // rustup looks for toolchain files from the project directory upwards
+        for dir in root.ancestors() {
+            for file_name in ["rust-toolchain.toml", "rust-toolchain"] {
+                let Ok(content) = fs::read_to_string(dir.join(file_name)) else {
+                    continue;
+                };
+                let channel = match toml::from_str::<toml::Value>(&content) {
+                    Ok(value) => value
+                        .get("toolchain")
+                        .and_then(|toolchain| toolchain.get("channel"))
+                        .and_then(|channel| channel.as_str())
+                        .map(str::to_string),
+                    // Legacy rust-toolchain files contain just the channel name
+                    Err(_) => content.lines().next().map(|line| line.trim().to_string()),
+                };
+                if let Some(channel) = channel.filter(|c| !c.is_empty()) {
+                    return Some((channel, file_name.to_string()));
+                }
+            }
+        }
+
+        let manifest = read_toml(&root.join("Cargo.toml"))?;
+        let rust_version = manifest
+            .get("package")
+            .and_then(|package| package.get("rust-version"))
+            .and_then(|version| version.as_str())?;
+        Some((rust_version.to_string(), "Cargo.toml".to_string()))
+    }
+
+    fn node_version(root: &Path) -> Option<(String, String)> {
+        for file_name in [".nvmrc", ".node-version"] {
+            if let Some(version) = read_first_line(&root.join(file_name)) {
+                return Some((version.trim_start_matches('v').to_string(), file_name.to_string()));
+            }
+        }
+
+        let content = fs::read_to_string(root.join("package.json")).ok()?;
+        let package: serde_json::Value = serde_json::from_str(&content).ok()?;
+        let version = package
+            .pointer("/volta/node")
+            .or_else(|| package.pointer("/engines/node"))
+            .and_then(|v| v.as_str())?;
+        Some((version.to_string(), "package.json".to_string()))
+    }
+
+    fn go_version(root: &Path) -> Option<(String, String)> {
+        let content = fs::read_to_string(root.join("go.mod")).ok()?;
+        let mut go_directive = None;
+
+        for line in content.lines() {
+            let line = line.trim();
+            // A toolchain directive is more specific than the language version
+            if let Some(toolchain) = line.strip_prefix("toolchain ") {
+                let version = toolchain.trim().trim_start_matches("go");
+                return Some((version.to_string(), "go.mod".to_string()));
+            }
+            if let Some(version) = line.strip_prefix("go ") {
+                go_directive = Some(version.trim().to_string());
+            }
+        }
+
+        go_directive.map(|version| (version, "go.mod".to_string()))
+    }
+
+    fn java_version(root: &Path) -> Option<(String, String)> {
+        if let Some(version) = read_first_line(&root.join(".java-version")) {
+            return Some((version, ".java-version".to_string()));
+        }
+
+        if let Ok(content) = fs::read_to_string(root.join(".sdkmanrc")) {
+            for line in content.lines() {
+                if let Some(version) = line.trim().strip_prefix("java=") {
+                    return Some((version.trim().to_string(), ".sdkmanrc".to_string()));
+                }
+            }
+        }
+
+        let pom = fs::read_to_string(root.join("pom.xml")).ok()?;
+        for property in ["maven.compiler.release", "java.version", "maven.compiler.source"] {
+            let open_tag = format!("<{}>", property);
+            let close_tag = format!("</{}>", property);
+            if let Some(start) = pom.find(&open_tag) {
+                let rest = &pom[start + open_tag.len()..];
+                if let Some(end) = rest.find(&close_tag) {
+                    return Some((rest[..end].trim().to_string(), "pom.xml".to_string()));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Fall back to an asdf/mise `.tool-versions` file
+    fn tool_versions(root: &Path, language: &str) -> Option<(String, String)> {
+        let tool_name = match language {
+            "node" => "nodejs",
+            "go" => "golang",
+            other => other,
+        };
+        let content = fs::read_to_string(root.join(".tool-versions")).ok()?;
+
+        content.lines().find_map(|line| {
+            let mut parts = line.split_whitespace();
+            if parts.next() == Some(tool_name) {
+                parts
+                    .next()
+                    .map(|version| (version.to_string(), ".tool-versions".to_string()))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+fn read_first_line(path: &Path) -> Option<String> {
+    let content = fs::read_to_string(path).ok()?;
+    content
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+}
+
+fn read_toml(path: &Path) -> Option<toml::Value> {
+    let content = fs::read_to_string(path).ok()?;
+    toml::from_str(&content).ok()
+}
+
+impl Segment for ToolchainSegment {
+    fn collect(&self, input: &InputData) -> Option<SegmentData> {
+        let project = self.detect_project(Path::new(&input.workspace.current_dir))?;
+        let version = if self.config.show_version {
+            Self::detect_version(&project)
+        } else {
+            None
+        };
+
+        let language_name = language_icon(project.language)
+            .map(|icon| icon.name)
+            .unwrap_or(project.language)
+            .to_string();
+
+        let (primary, secondary) = match version {
+            Some((ref version, _)) if self.config.show_language => {
+                (language_name.clone(), version.clone())
+            }
+            Some((ref version, _)) => (version.clone(), String::new()),
+            None => (language_name.clone(), String::new()),
+        };
+
+        let mut metadata = HashMap::new();
+        metadata.insert("language".to_string(), project.language.to_string());
+        metadata.insert("language_name".to_string(), language_name);
+        metadata.insert("marker".to_string(), project.marker.to_string());
+        metadata.insert(
+            "project_root".to_string(),
+            project.root.to_string_lossy().to_string(),
+        );
+        if let Some((version, source)) = version {
+            metadata.insert("version".to_string(), version);
+            metadata.insert("version_source".to_string(), source);
+        }
+
+        Some(SegmentData {
+            primary,
+            secondary,
+            metadata,
+        })
+    }
+
+    fn id(&self) -> SegmentId {
+        SegmentId::Toolchain
+    }
+
+    fn resolve_style(&self, config: &mut SegmentConfig, data: &SegmentData) {
+        if !self.config.use_language_icon {
+            return;
+        }
+
+        if let Some(icon) = data
+            .metadata
+            .get("language")
+            .and_then(|language| language_icon(language))
+        {
+            config.icon.plain = icon.plain.to_string();
+            config.icon.nerd_font = icon.nerd_font.to_string();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::utils::test_dir::TestDir;
+
+    fn segment(options: &[(&str, bool)]) -> ToolchainSegment {
+        let options = options
+            .iter()
+            .map(|(key, value)| (key.to_string(), serde_json::Value::Bool(*value)))
+            .collect();
+        ToolchainSegment::with_config(&options)
+    }
+
+    #[test]
+    fn detects_project_root_and_pinned_versions() {
+        let root = TestDir::new("toolchain");
+        let nested = root.join("src").join("bin");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nrust-version = \"1.80\"\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("go.mod"),
+            "module demo\n\ngo 1.22\ntoolchain go1.22.5\n",
+        )
+        .unwrap();
+        fs::write(root.join(".nvmrc"), "# lts\nv20.11.1\n").unwrap();
+        fs::write(
+            root.join(".tool-versions"),
+            "golang 1.21.0\njava temurin-21\n",
+        )
+        .unwrap();
+
+        let project = segment(&[]).detect_project(&nested);
+        let shallow = segment(&[("search_ancestors", false)]).detect_project(&nested);
+        let rust = ToolchainSegment::rust_version(&root);
+        let go = ToolchainSegment::go_version(&root);
+        let node = ToolchainSegment::node_version(&root);
+        let java = ToolchainSegment::tool_versions(&root, "java");
+
+        let project = project.unwrap();
+        assert_eq!(
+            (project.root, project.language),
+            (root.to_path_buf(), "rust")
+        );
+        assert!(shallow.is_none());
+        assert_eq!(rust, Some(("1.80".to_string(), "Cargo.toml".to_string())));
+        assert_eq!(go, Some(("1.22.5".to_string(), "go.mod".to_string())));
+        assert_eq!(node, Some(("20.11.1".to_string(), ".nvmrc".to_string())));
+        assert_eq!(
+            java,
+            Some(("temurin-21".to_string(), ".tool-versions".to_string()))
+        );
+    }
+
+    #[test]
+    fn language_icon_replaces_configured_icon_only_when_enabled() {
+        let mut config = Config::default().segments[0].clone();
+        config.icon.plain = "T".to_string();
+        let data = SegmentData {
+            primary: "1.80".to_string(),
+            secondary: String::new(),
+            metadata: HashMap::from([("language".to_string(), "rust".to_string())]),
+        };
+
+        segment(&[]).resolve_style(&mut config, &data);
+        assert_eq!(config.icon.plain, "T");
+
+        segment(&[("use_language_icon", true)]).resolve_style(&mut config, &data);
+        assert_eq!(config.icon.plain, "🦀");
+    }
+}
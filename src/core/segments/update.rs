@@ -1,13 +1,22 @@
 use super::{Segment, SegmentData};
-use crate::config::{InputData, SegmentId};
+use crate::config::{InputData, SegmentId, UpdateSegmentConfig};
 use crate::updater::UpdateState;
+use std::collections::HashMap;
 
 #[derive(Default)]
-pub struct UpdateSegment;
+pub struct UpdateSegment {
+    config: UpdateSegmentConfig,
+}
 
 impl UpdateSegment {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    pub fn with_config(options: &HashMap<String, serde_json::Value>) -> Self {
+        Self {
+            config: UpdateSegmentConfig::from_options(options),
+        }
     }
 }
 
@@ -16,10 +25,19 @@ impl Segment for UpdateSegment {
         // Load update state and check for update status
         let update_state = UpdateState::load();
 
-        update_state.status_text().map(|status_text| SegmentData {
-            primary: status_text,
-            secondary: String::new(),
-            metadata: std::collections::HashMap::new(),
+        update_state.status_text().map(|status_text| {
+            let mut metadata = HashMap::new();
+            if self.config.hyperlink {
+                if let Some(url) = update_state.release_notes_url() {
+                    metadata.insert("hyperlink".to_string(), url);
+                }
+            }
+
+            SegmentData {
+                primary: status_text,
+                secondary: String::new(),
+                metadata,
+            }
         })
     }
 
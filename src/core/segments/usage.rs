@@ -116,6 +116,11 @@ enum UsageStatus {
 
 impl Segment for UsageSegment {
     fn collect(&self, input: &InputData) -> Option<SegmentData> {
+        // No transcript outside a Claude Code session, e.g. in a tmux status line
+        if input.transcript_path.is_empty() {
+            return None;
+        }
+
         // Dynamically determine context limit based on current model ID
         let context_limit = Self::get_context_limit_for_model(&input.model.id);
 
@@ -153,9 +158,10 @@ impl Segment for UsageSegment {
                     },
                 };
 
-                let secondary = if self.config.show_limit && 
-                    self.config.display_format != UsageDisplayFormat::Tokens &&
-                    !(self.config.display_format == UsageDisplayFormat::Bar && self.config.bar_show_tokens && self.config.show_limit) {
+                let secondary = if self.config.show_limit
+                    && self.config.display_format != UsageDisplayFormat::Tokens
+                    && !(self.config.display_format == UsageDisplayFormat::Bar && self.config.bar_show_tokens)
+                {
                     let limit_str = self.format_tokens(context_limit);
                     format!("/{}", limit_str)
                 } else {
@@ -391,3 +397,18 @@ fn try_find_usage_from_project_history(transcript_path: &Path) -> Option<u32> {
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hidden_without_a_transcript() {
+        let mut input = InputData::for_directory("/tmp");
+        assert!(UsageSegment::new().collect(&input).is_none());
+
+        // Inside a session the transcript may not have usage yet
+        input.transcript_path = "/nonexistent/transcript.jsonl".to_string();
+        assert!(UsageSegment::new().collect(&input).is_some());
+    }
+}
@@ -0,0 +1,288 @@
+//! Repository detection and the non-git backends shown by the Git segment.
+//! jj and Mercurial report through the same [`RepositoryInfo`] as git so the
+//! segment renders them in the same slots.
+
+use super::git::{GitStatus, GitStatusCounts};
+use crate::config::VcsKind;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// What every supported VCS can report about the working copy
+pub trait RepositoryInfo {
+    fn vcs(&self) -> VcsKind;
+    /// Branch, bookmark or change id, shown as the segment's primary text
+    fn head(&self) -> &str;
+    fn status(&self) -> &GitStatus;
+    fn status_counts(&self) -> &GitStatusCounts;
+    /// Short commit or change id, when the VCS has one distinct from `head`
+    fn change_id(&self) -> Option<&str> {
+        None
+    }
+}
+
+impl VcsKind {
+    fn marker(&self) -> &'static str {
+        match self {
+            VcsKind::Git => ".git",
+            VcsKind::Jujutsu => ".jj",
+            VcsKind::Mercurial => ".hg",
+        }
+    }
+}
+
+/// Find the nearest repository above `dir`. When one directory holds several
+/// (jj colocated with git), the first kind in `order` wins.
+pub fn detect(dir: &Path, order: &[VcsKind]) -> Option<(VcsKind, PathBuf)> {
+    dir.ancestors().find_map(|ancestor| {
+        order
+            .iter()
+            .find(|kind| ancestor.join(kind.marker()).exists())
+            .map(|kind| (*kind, ancestor.to_path_buf()))
+    })
+}
+
+fn run(program: &str, args: &[&str], working_dir: &str) -> Option<String> {
+    let output = Command::new(program)
+        .args(args)
+        .current_dir(working_dir)
+        // Stable, uncolored output for parsing
+        .env("HGPLAIN", "1")
+        .output()
+        .ok()?;
+
+    if output.status.success() {
+        String::from_utf8(output.stdout).ok()
+    } else {
+        None
+    }
+}
+
+fn status_from_counts(counts: &GitStatusCounts) -> GitStatus {
+    if counts.conflicted > 0 {
+        GitStatus::Conflicts
+    } else if counts.unstaged + counts.untracked > 0 {
+        GitStatus::Dirty
+    } else {
+        GitStatus::Clean
+    }
+}
+
+#[derive(Debug)]
+pub struct JjInfo {
+    pub change_id: String,
+    pub bookmark: Option<String>,
+    pub status: GitStatus,
+    pub status_counts: GitStatusCounts,
+}
+
+impl RepositoryInfo for JjInfo {
+    fn vcs(&self) -> VcsKind {
+        VcsKind::Jujutsu
+    }
+
+    fn head(&self) -> &str {
+        self.bookmark.as_deref().unwrap_or(&self.change_id)
+    }
+
+    fn status(&self) -> &GitStatus {
+        &self.status
+    }
+
+    fn status_counts(&self) -> &GitStatusCounts {
+        &self.status_counts
+    }
+
+    fn change_id(&self) -> Option<&str> {
+        self.bookmark.as_ref().map(|_| self.change_id.as_str())
+    }
+}
+
+/// Working-copy commit `@`, named after its bookmark or its parent's
+pub fn jj_info(working_dir: &str) -> Option<JjInfo> {
+    // This first command also snapshots the working copy
+    let log = run(
+        "jj",
+        &[
+            "log",
+            "-r",
+            "@ | @-",
+            "--no-graph",
+            "--color",
+            "never",
+            "-T",
+            r#"change_id.shortest(8) ++ "\t" ++ local_bookmarks.join(",") ++ "\t" ++ if(conflict, "1", "0") ++ "\n""#,
+        ],
+        working_dir,
+    )?;
+
+    let mut entries = log.lines().map(|line| {
+        let mut fields = line.split('\t');
+        let change_id = fields.next().unwrap_or_default().to_string();
+        let bookmark = fields
+            .next()
+            .and_then(|names| names.split(',').next())
+            .map(|name| name.trim_end_matches(['*', '?']).to_string())
+            .filter(|name| !name.is_empty());
+        let conflict = fields.next() == Some("1");
+        (change_id, bookmark, conflict)
+    });
+
+    let (change_id, bookmark, conflict) = entries.next()?;
+    let bookmark = bookmark.or_else(|| entries.find_map(|(_, bookmark, _)| bookmark));
+
+    let mut counts = GitStatusCounts::default();
+    let summary = run(
+        "jj",
+        &["diff", "-r", "@", "--summary", "--ignore-working-copy", "--color", "never"],
+        working_dir,
+    )
+    .unwrap_or_default();
+    for line in summary.lines() {
+        // jj has no index, so every change is unstaged
+        match line.chars().next() {
+            Some('A') | Some('C') => counts.added += 1,
+            Some('D') => counts.deleted += 1,
+            Some('R') => {
+                counts.renamed += 1;
+                counts.modified += 1;
+            }
+            Some(_) => counts.modified += 1,
+            None => continue,
+        }
+        counts.unstaged += 1;
+    }
+
+    if conflict {
+        counts.conflicted = run(
+            "jj",
+            &["resolve", "--list", "--ignore-working-copy", "--color", "never"],
+            working_dir,
+        )
+        .map(|list| list.lines().count() as u32)
+        .unwrap_or(1)
+        .max(1);
+    }
+
+    Some(JjInfo {
+        change_id,
+        bookmark,
+        status: status_from_counts(&counts),
+        status_counts: counts,
+    })
+}
+
+#[derive(Debug)]
+pub struct HgInfo {
+    pub node: String,
+    pub head: String,
+    pub status: GitStatus,
+    pub status_counts: GitStatusCounts,
+}
+
+impl RepositoryInfo for HgInfo {
+    fn vcs(&self) -> VcsKind {
+        VcsKind::Mercurial
+    }
+
+    fn head(&self) -> &str {
+        &self.head
+    }
+
+    fn status(&self) -> &GitStatus {
+        &self.status
+    }
+
+    fn status_counts(&self) -> &GitStatusCounts {
+        &self.status_counts
+    }
+
+    fn change_id(&self) -> Option<&str> {
+        Some(&self.node)
+    }
+}
+
+/// Working directory parent, named after the active bookmark or the branch
+pub fn hg_info(working_dir: &str, root: &Path) -> Option<HgInfo> {
+    let log = run(
+        "hg",
+        &["log", "-r", ".", "-T", "{node|short}\\t{branch}\\t{activebookmark}"],
+        working_dir,
+    )?;
+    let mut fields = log.trim_end().split('\t');
+    let node = fields.next().unwrap_or_default().to_string();
+    let branch = fields.next().unwrap_or("default").to_string();
+    let head = fields
+        .next()
+        .filter(|bookmark| !bookmark.is_empty())
+        .map(|bookmark| bookmark.to_string())
+        .unwrap_or(branch);
+
+    let mut counts = GitStatusCounts::default();
+    let status = run("hg", &["status"], working_dir).unwrap_or_default();
+    for line in status.lines() {
+        match line.chars().next() {
+            Some('?') => {
+                counts.untracked += 1;
+                counts.modified += 1;
+                continue;
+            }
+            Some('A') => counts.added += 1,
+            Some('R') | Some('!') => counts.deleted += 1,
+            Some('M') => counts.modified += 1,
+            _ => continue,
+        }
+        counts.unstaged += 1;
+    }
+
+    // Unresolved files only exist while a merge is in progress
+    if root.join(".hg").join("merge").exists() {
+        counts.conflicted = run("hg", &["resolve", "--list"], working_dir)
+            .map(|list| list.lines().filter(|line| line.starts_with("U ")).count() as u32)
+            .unwrap_or(0);
+    }
+
+    Some(HgInfo {
+        node,
+        head,
+        status: status_from_counts(&counts),
+        status_counts: counts,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_dir::TestDir;
+    use std::fs;
+
+    #[test]
+    fn detects_nearest_repository_in_preference_order() {
+        let root = TestDir::new("vcs");
+        let nested = root.join("hg-repo").join("src");
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::create_dir_all(root.join(".jj")).unwrap();
+        fs::create_dir_all(root.join("hg-repo").join(".hg")).unwrap();
+        fs::create_dir_all(&nested).unwrap();
+
+        let all = [VcsKind::Jujutsu, VcsKind::Git, VcsKind::Mercurial];
+        let colocated = detect(&root, &all);
+        let git_first = detect(&root, &[VcsKind::Git, VcsKind::Jujutsu]);
+        let nearest = detect(&nested, &all);
+        let git_only = detect(&nested, &[VcsKind::Git]);
+
+        assert_eq!(colocated, Some((VcsKind::Jujutsu, root.to_path_buf())));
+        assert_eq!(git_first, Some((VcsKind::Git, root.to_path_buf())));
+        assert_eq!(nearest, Some((VcsKind::Mercurial, root.join("hg-repo"))));
+        assert_eq!(git_only, Some((VcsKind::Git, root.to_path_buf())));
+    }
+
+    #[test]
+    fn status_summarises_counts() {
+        let mut counts = GitStatusCounts::default();
+        assert_eq!(status_from_counts(&counts), GitStatus::Clean);
+        counts.untracked = 1;
+        assert_eq!(status_from_counts(&counts), GitStatus::Dirty);
+        counts.conflicted = 1;
+        assert_eq!(status_from_counts(&counts), GitStatus::Conflicts);
+    }
+}
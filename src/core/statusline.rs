@@ -1,54 +1,147 @@
-use crate::config::{AnsiColor, Config, SegmentConfig, StyleMode};
+use crate::config::{
+    AnsiColor, ColorConfig, Config, SegmentConfig, SegmentId, StyleMode, WidthConfig,
+};
+use crate::core::color::ColorSupport;
+use crate::core::json::{SegmentJson, StatusLineJson, SCHEMA_VERSION};
+use crate::core::output::OutputFormat;
+use crate::core::powerline::{self, PowerlineFamily};
 use crate::core::segments::SegmentData;
+use crate::core::width::display_width;
+use std::time::{Duration, Instant};
 
-/// Strip ANSI escape sequences and return visible text length
-fn visible_width(text: &str) -> usize {
+/// Strip ANSI escape sequences and return the display width in cells.
+/// Handles CSI sequences (colors) and OSC sequences (hyperlinks).
+fn visible_width(text: &str, widths: &WidthConfig) -> usize {
     let mut visible = String::new();
-    let mut in_escape = false;
     let mut chars = text.chars().peekable();
 
     while let Some(ch) = chars.next() {
-        if ch == '\x1b' {
-            // Start of ANSI escape sequence
-            in_escape = true;
-            // Skip the [ character
-            if chars.peek() == Some(&'[') {
-                chars.next();
-            }
-        } else if in_escape {
-            // Skip until we find the end of the escape sequence (letter)
-            if ch.is_alphabetic() {
-                in_escape = false;
-            }
-        } else {
+        if ch != '\x1b' {
             // Regular character
             visible.push(ch);
+            continue;
+        }
+
+        match chars.next() {
+            // CSI: skip until the final letter
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if c.is_alphabetic() {
+                        break;
+                    }
+                }
+            }
+            // OSC: skip until BEL or the string terminator ESC \
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' {
+                        if chars.peek() == Some(&'\\') {
+                            chars.next();
+                        }
+                        break;
+                    }
+                }
+            }
+            _ => {}
         }
     }
 
-    visible.chars().count()
+    display_width(&visible, widths)
+}
+
+/// Wrap text in an OSC 8 hyperlink
+fn hyperlink(text: &str, url: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
 }
 
 pub struct StatusLineGenerator {
     config: Config,
+    color_support: ColorSupport,
+    output_format: OutputFormat,
 }
 
 impl StatusLineGenerator {
     pub fn new(config: Config) -> Self {
-        Self { config }
+        let color_support = ColorSupport::resolve(config.style.color_mode);
+        Self {
+            config,
+            color_support,
+            output_format: OutputFormat::Ansi,
+        }
     }
 
-    pub fn generate(&self, segments: Vec<(SegmentConfig, SegmentData)>) -> String {
-        let mut output = Vec::new();
-        let enabled_segments: Vec<_> = segments
+    /// Emit tmux markup, prompt escapes or plain text instead of ANSI
+    pub fn with_output_format(mut self, output_format: OutputFormat) -> Self {
+        self.output_format = output_format;
+        self
+    }
+
+    /// Enabled segments with their colors converted to what the terminal supports
+    fn prepare_segments(
+        &self,
+        segments: Vec<(SegmentConfig, SegmentData)>,
+    ) -> Vec<(SegmentConfig, SegmentData)> {
+        segments
             .into_iter()
             .filter(|(config, _)| config.enabled)
-            .collect();
+            .map(|(mut config, data)| {
+                config.colors = self.adapt_colors(&config.colors);
+                (config, data)
+            })
+            .collect()
+    }
+
+    /// Segment colors converted to what the terminal supports
+    fn adapt_colors(&self, colors: &ColorConfig) -> ColorConfig {
+        let adapt = |color: &Option<AnsiColor>| {
+            color
+                .as_ref()
+                .and_then(|color| self.color_support.adapt(color))
+        };
+        // Without colors a background still decides the padded layout; it emits no codes
+        let background = match self.color_support {
+            ColorSupport::None => colors.background.clone(),
+            _ => adapt(&colors.background),
+        };
+
+        ColorConfig {
+            icon: adapt(&colors.icon),
+            text: adapt(&colors.text),
+            background,
+        }
+    }
+
+    /// Separator between segments, white unless colors are disabled
+    fn plain_separator(&self) -> String {
+        if self.color_support == ColorSupport::None {
+            self.config.style.separator.clone()
+        } else {
+            format!("\x1b[37m{}\x1b[0m", self.config.style.separator)
+        }
+    }
+
+    /// Reset sequence, empty when colors are disabled
+    fn reset(&self) -> &'static str {
+        if self.color_support == ColorSupport::None {
+            ""
+        } else {
+            "\x1b[0m"
+        }
+    }
+
+    pub fn generate(&self, segments: Vec<(SegmentConfig, SegmentData)>) -> String {
+        let mut output = Vec::new();
+        let mut segment_configs = Vec::new();
+        let enabled_segments = self.prepare_segments(segments);
 
         for (config, data) in enabled_segments.iter() {
             let rendered = self.render_segment(config, data);
             if !rendered.is_empty() {
                 output.push(rendered);
+                segment_configs.push(config);
             }
         }
 
@@ -56,13 +149,68 @@ impl StatusLineGenerator {
             return String::new();
         }
 
-        // Handle Powerline arrow separators with color transition
-        if self.config.style.separator == "\u{e0b0}" {
-            self.join_with_powerline_arrows(&output, &enabled_segments)
+        // Handle Powerline separators with color transition
+        let line = if let Some(family) = powerline::family_for(&self.config.style.separator) {
+            self.join_with_powerline_separators(&output, &segment_configs, family)
         } else {
             // For all other separators, use white color and simple join
             self.join_with_white_separators(&output)
-        }
+        };
+
+        let line = format!(
+            "{}{}{}",
+            self.start_cap(segment_configs[0]),
+            line,
+            self.end_cap(segment_configs[segment_configs.len() - 1])
+        );
+        self.output_format.convert(&line)
+    }
+
+    /// Collected segments and the rendered line as versioned JSON
+    pub fn generate_json(&self, segments: Vec<TimedSegment>, elapsed: Duration) -> String {
+        let ansi = self.generate(
+            segments
+                .iter()
+                .map(|segment| (segment.config.clone(), segment.data.clone()))
+                .collect(),
+        );
+
+        let segments = segments
+            .into_iter()
+            .filter(|segment| segment.config.enabled)
+            .map(|segment| {
+                let TimedSegment {
+                    mut config,
+                    data,
+                    elapsed,
+                } = segment;
+                config.colors = self.adapt_colors(&config.colors);
+                let rendered = self.render_segment(&config, &data);
+                SegmentJson {
+                    id: config.id,
+                    key: config.key(),
+                    name: config.name.clone(),
+                    icon: self.get_icon(&config),
+                    primary: data.primary,
+                    secondary: data.secondary,
+                    metadata: data.metadata.into_iter().collect(),
+                    colors: config.colors,
+                    bold: config.styles.text_bold,
+                    ansi: rendered,
+                    elapsed_us: elapsed.as_micros() as u64,
+                }
+            })
+            .collect();
+
+        let json = StatusLineJson {
+            version: SCHEMA_VERSION,
+            color_support: self.color_support.name(),
+            plain: OutputFormat::Plain.convert(&ansi),
+            ansi,
+            elapsed_us: elapsed.as_micros() as u64,
+            segments,
+        };
+        serde_json::to_string(&json).unwrap_or_default()
     }
 
     /// Generate statusline for TUI preview with proper width calculation
@@ -97,10 +245,7 @@ impl StatusLineGenerator {
         use ansi_to_tui::IntoText;
         use ratatui::text::{Line, Span, Text};
 
-        let enabled_segments: Vec<_> = segments
-            .into_iter()
-            .filter(|(config, _)| config.enabled)
-            .collect();
+        let enabled_segments = self.prepare_segments(segments);
 
         if enabled_segments.is_empty() {
             return Text::from(vec![Line::default()]);
@@ -122,21 +267,21 @@ impl StatusLineGenerator {
             return Text::from(vec![Line::default()]);
         }
 
+        // Caps belong to the first and last segments when wrapping
+        let last = rendered_segments.len() - 1;
+        rendered_segments[0].insert_str(0, &self.start_cap(&segment_configs[0]));
+        rendered_segments[last].push_str(&self.end_cap(&segment_configs[last]));
+
         // Pre-calculate separators between segments
+        let family = powerline::family_for(&self.config.style.separator);
         let mut separators = Vec::new();
         for i in 0..rendered_segments.len().saturating_sub(1) {
-            let separator = if self.config.style.separator == "\u{e0b0}" {
-                // Powerline arrows with color transition
-                let prev_bg = segment_configs
-                    .get(i)
-                    .and_then(|config| config.colors.background.as_ref());
-                let curr_bg = segment_configs
-                    .get(i + 1)
-                    .and_then(|config| config.colors.background.as_ref());
-                self.create_powerline_arrow(prev_bg, curr_bg)
+            let separator = if let Some(family) = family {
+                // Powerline separators with color transition
+                self.powerline_separator(&segment_configs[i], &segment_configs[i + 1], family)
             } else {
                 // Regular separators with white color
-                format!("\x1b[37m{}\x1b[0m", self.config.style.separator)
+                self.plain_separator()
             };
             separators.push(separator);
         }
@@ -146,10 +291,11 @@ impl StatusLineGenerator {
         let mut current_line = String::new();
         let mut current_width = 0usize;
         let max_w = max_width as usize;
+        let widths = &self.config.style.width;
 
         for i in 0..rendered_segments.len() {
             let segment = &rendered_segments[i];
-            let segment_width = visible_width(segment);
+            let segment_width = visible_width(segment, widths);
 
             // Check if adding this segment would exceed max_width
             if current_width > 0 && current_width + segment_width > max_w {
@@ -166,12 +312,12 @@ impl StatusLineGenerator {
             // Handle separator if not the last segment
             if i < separators.len() {
                 let separator = &separators[i];
-                let separator_width = visible_width(separator);
+                let separator_width = visible_width(separator, widths);
 
                 // Check if next segment exists
                 if i + 1 < rendered_segments.len() {
                     let next_segment = &rendered_segments[i + 1];
-                    let next_width = visible_width(next_segment);
+                    let next_width = visible_width(next_segment, widths);
 
                     // Check if separator AND next segment both fit
                     if current_width + separator_width + next_width <= max_w {
@@ -217,6 +363,20 @@ impl StatusLineGenerator {
     fn render_segment(&self, config: &SegmentConfig, data: &SegmentData) -> String {
         let icon = self.get_icon(config);
 
+        // Built-in segments that opted into hyperlinks provide the target in
+        // metadata; command-driven segments can't inject links
+        let link = match config.id {
+            SegmentId::Directory | SegmentId::Git | SegmentId::Update => data
+                .metadata
+                .get("hyperlink")
+                .filter(|url| crate::core::ansi::is_allowed_link(url)),
+            _ => None,
+        };
+        let primary = match link {
+            Some(url) => hyperlink(&data.primary, url),
+            None => data.primary.clone(),
+        };
+
         // Apply background color to the entire segment if set
         if let Some(bg_color) = &config.colors.background {
             let bg_code = self.apply_background_color(bg_color);
@@ -231,7 +391,7 @@ impl StatusLineGenerator {
 
             let text_styled = self
                 .apply_style(
-                    &data.primary,
+                    &primary,
                     config.colors.text.as_ref(),
                     config.styles.text_bold,
                 )
@@ -251,12 +411,13 @@ impl StatusLineGenerator {
             }
 
             // Apply background to the entire content and reset at the end
-            format!("{}{}\x1b[49m", bg_code, segment_content)
+            let bg_reset = if bg_code.is_empty() { "" } else { "\x1b[49m" };
+            format!("{}{}{}", bg_code, segment_content, bg_reset)
         } else {
             // No background color, use original logic
             let icon_colored = self.apply_color(&icon, config.colors.icon.as_ref());
             let text_styled = self.apply_style(
-                &data.primary,
+                &primary,
                 config.colors.text.as_ref(),
                 config.styles.text_bold,
             );
@@ -287,6 +448,9 @@ impl StatusLineGenerator {
     }
 
     fn apply_color(&self, text: &str, color: Option<&AnsiColor>) -> String {
+        if self.color_support == ColorSupport::None {
+            return text.to_string();
+        }
         match color {
             Some(AnsiColor::Color16 { c16 }) => {
                 let code = if *c16 < 8 { 30 + c16 } else { 90 + (c16 - 8) };
@@ -303,6 +467,10 @@ impl StatusLineGenerator {
     }
 
     fn apply_style(&self, text: &str, color: Option<&AnsiColor>, bold: bool) -> String {
+        // No SGR at all without colors, bold included (NO_COLOR)
+        if self.color_support == ColorSupport::None {
+            return text.to_string();
+        }
         let mut codes = Vec::new();
 
         // Add style codes
@@ -339,6 +507,9 @@ impl StatusLineGenerator {
     }
 
     fn apply_background_color(&self, color: &AnsiColor) -> String {
+        if self.color_support == ColorSupport::None {
+            return String::new();
+        }
         match color {
             AnsiColor::Color16 { c16 } => {
                 let code = if *c16 < 8 { 40 + c16 } else { 100 + (c16 - 8) };
@@ -360,15 +531,15 @@ impl StatusLineGenerator {
         }
 
         // Use white color for separator
-        let white_separator = format!("\x1b[37m{}\x1b[0m", self.config.style.separator);
-        rendered_segments.join(&white_separator)
+        rendered_segments.join(&self.plain_separator())
     }
 
-    /// Join segments with Powerline arrow separators with proper color transitions
-    fn join_with_powerline_arrows(
+    /// Join segments with Powerline separators with proper color transitions
+    fn join_with_powerline_separators(
         &self,
         rendered_segments: &[String],
-        segment_configs: &[(SegmentConfig, SegmentData)],
+        segment_configs: &[&SegmentConfig],
+        family: &PowerlineFamily,
     ) -> String {
         if rendered_segments.is_empty() {
             return String::new();
@@ -381,50 +552,72 @@ impl StatusLineGenerator {
         let mut result = rendered_segments[0].clone();
 
         for (i, _) in rendered_segments.iter().enumerate().skip(1) {
-            let prev_bg = segment_configs
-                .get(i - 1)
-                .and_then(|(config, _)| config.colors.background.as_ref());
-            let curr_bg = segment_configs
-                .get(i)
-                .and_then(|(config, _)| config.colors.background.as_ref());
-
-            // Create Powerline arrow with color transition
-            let arrow = self.create_powerline_arrow(prev_bg, curr_bg);
+            let separator =
+                self.powerline_separator(segment_configs[i - 1], segment_configs[i], family);
 
-            result.push_str(&arrow);
+            result.push_str(&separator);
             result.push_str(&rendered_segments[i]);
         }
 
         // Reset colors at the end
-        result.push_str("\x1b[0m");
+        result.push_str(self.reset());
         result
     }
 
+    /// Separator between two segments: the thin glyph in the text color when
+    /// they share a background, otherwise the solid glyph as a color transition
+    fn powerline_separator(
+        &self,
+        prev: &SegmentConfig,
+        curr: &SegmentConfig,
+        family: &PowerlineFamily,
+    ) -> String {
+        let prev_bg = prev.colors.background.as_ref();
+        let curr_bg = curr.colors.background.as_ref();
+
+        match (prev_bg, family.thin) {
+            (Some(bg), Some(thin)) if prev_bg == curr_bg => {
+                let fg_code = match &prev.colors.text {
+                    Some(text) => self.color_to_foreground_code(text),
+                    None if self.color_support == ColorSupport::None => String::new(),
+                    None => "\x1b[37m".to_string(),
+                };
+                format!(
+                    "{}{}{}{}",
+                    self.apply_background_color(bg),
+                    fg_code,
+                    thin,
+                    self.reset()
+                )
+            }
+            _ => self.create_powerline_arrow(prev_bg, curr_bg, family.solid),
+        }
+    }
+
     /// Create a Powerline arrow with proper color transition
     fn create_powerline_arrow(
         &self,
         prev_bg: Option<&AnsiColor>,
         curr_bg: Option<&AnsiColor>,
+        arrow_char: &str,
     ) -> String {
-        let arrow_char = "\u{e0b0}";
-
         match (prev_bg, curr_bg) {
             (Some(prev), Some(curr)) => {
                 // Arrow foreground = previous segment's background
                 // Arrow background = current segment's background
                 let fg_code = self.color_to_foreground_code(prev);
                 let bg_code = self.apply_background_color(curr);
-                format!("{}{}{}\x1b[0m", bg_code, fg_code, arrow_char)
+                format!("{}{}{}{}", bg_code, fg_code, arrow_char, self.reset())
             }
             (Some(prev), None) => {
                 // Previous segment has background, current doesn't
                 let fg_code = self.color_to_foreground_code(prev);
-                format!("{}{}\x1b[0m", fg_code, arrow_char)
+                format!("{}{}{}", fg_code, arrow_char, self.reset())
             }
             (None, Some(curr)) => {
                 // Current segment has background, previous doesn't
                 let bg_code = self.apply_background_color(curr);
-                format!("{}{}\x1b[0m", bg_code, arrow_char)
+                format!("{}{}{}", bg_code, arrow_char, self.reset())
             }
             (None, None) => {
                 // Neither segment has background color
@@ -433,8 +626,33 @@ impl StatusLineGenerator {
         }
     }
 
+    /// Start cap drawn in the first segment's background color
+    fn start_cap(&self, first: &SegmentConfig) -> String {
+        self.cap(&self.config.style.caps.start, first)
+    }
+
+    /// End cap drawn in the last segment's background color
+    fn end_cap(&self, last: &SegmentConfig) -> String {
+        self.cap(&self.config.style.caps.end, last)
+    }
+
+    fn cap(&self, glyph: &str, segment: &SegmentConfig) -> String {
+        match &segment.colors.background {
+            Some(bg) if !glyph.is_empty() => format!(
+                "{}{}{}",
+                self.color_to_foreground_code(bg),
+                glyph,
+                self.reset()
+            ),
+            _ => glyph.to_string(),
+        }
+    }
+
     /// Convert AnsiColor to foreground color code
     fn color_to_foreground_code(&self, color: &AnsiColor) -> String {
+        if self.color_support == ColorSupport::None {
+            return String::new();
+        }
         match color {
             AnsiColor::Color16 { c16 } => {
                 let code = if *c16 < 8 { 30 + c16 } else { 90 + (c16 - 8) };
@@ -450,54 +668,245 @@ impl StatusLineGenerator {
     }
 }
 
+/// A collected segment and how long its collection took
+pub struct TimedSegment {
+    pub config: SegmentConfig,
+    pub data: SegmentData,
+    pub elapsed: Duration,
+}
+
 pub fn collect_all_segments(
     config: &Config,
     input: &crate::config::InputData,
 ) -> Vec<(SegmentConfig, SegmentData)> {
-    use crate::core::segments::*;
+    collect_all_segments_timed(config, input)
+        .into_iter()
+        .map(|segment| (segment.config, segment.data))
+        .collect()
+}
 
-    let mut results = Vec::new();
+/// Collect all segments, timing each one
+pub fn collect_all_segments_timed(
+    config: &Config,
+    input: &crate::config::InputData,
+) -> Vec<TimedSegment> {
+    use crate::core::segments::SegmentRegistry;
+
+    let registry = SegmentRegistry::new();
+    let mut slots = Vec::with_capacity(config.segments.len());
+    let mut deferred = Vec::new();
+
+    for (index, segment_config) in config.segments.iter().enumerate() {
+        // Disabled segments are never created, so their commands never run
+        if !segment_config.enabled {
+            slots.push(None);
+            continue;
+        }
+        let segment = registry.create(segment_config);
 
-    for segment_config in &config.segments {
-        let segment_data = match segment_config.id {
-            crate::config::SegmentId::Model => {
-                let segment = ModelSegment::with_config(&segment_config.options);
-                segment.collect(input)
-            }
-            crate::config::SegmentId::Directory => {
-                let segment = DirectorySegment::new().with_config(&segment_config.options);
-                segment.collect(input)
-            }
-            crate::config::SegmentId::Git => {
-                let segment = GitSegment::with_config(&segment_config.options);
-                segment.collect(input)
-            }
-            crate::config::SegmentId::Usage => {
-                let segment = UsageSegment::new().with_config(&segment_config.options);
-                segment.collect(input)
-            }
-            crate::config::SegmentId::Cost => {
-                let segment = CostSegment::with_config(&segment_config.options);
-                segment.collect(input)
-            }
-            crate::config::SegmentId::Session => {
-                let segment = SessionSegment::with_config(&segment_config.options);
-                segment.collect(input)
-            }
-            crate::config::SegmentId::OutputStyle => {
-                let segment = OutputStyleSegment::with_config(&segment_config.options);
-                segment.collect(input)
-            }
-            crate::config::SegmentId::Update => {
-                let segment = UpdateSegment::new();
-                segment.collect(input)
+        if segment.depends_on_segments() {
+            slots.push(None);
+            deferred.push((index, segment_config, segment));
+            continue;
+        }
+
+        let started = Instant::now();
+        slots.push(segment.collect(input).map(|data| {
+            let mut resolved_config = segment_config.clone();
+            segment.resolve_style(&mut resolved_config, &data);
+            TimedSegment {
+                config: resolved_config,
+                data,
+                elapsed: started.elapsed(),
             }
-        };
+        }));
+    }
 
-        if let Some(data) = segment_data {
-            results.push((segment_config.clone(), data));
+    // Second pass: segments that read the results of the first pass
+    if !deferred.is_empty() {
+        let collected: Vec<_> = slots
+            .iter()
+            .flatten()
+            .map(|segment| (segment.config.clone(), segment.data.clone()))
+            .collect();
+        for (index, segment_config, segment) in deferred {
+            let started = Instant::now();
+            slots[index] = segment
+                .collect_with_segments(input, &collected)
+                .map(|data| {
+                    let mut resolved_config = segment_config.clone();
+                    segment.resolve_style(&mut resolved_config, &data);
+                    TimedSegment {
+                        config: resolved_config,
+                        data,
+                        elapsed: started.elapsed(),
+                    }
+                });
         }
     }
 
-    results
+    slots
+        .into_iter()
+        .flatten()
+        .filter(|segment| is_segment_visible(&segment.config, &segment.data))
+        .collect()
+}
+
+/// Apply the segment's `show_when`/`hide_when` rules to its collected data
+pub(crate) fn is_segment_visible(config: &SegmentConfig, data: &SegmentData) -> bool {
+    if config.show_when.is_none() && config.hide_when.is_none() {
+        return true;
+    }
+
+    let mut values = data.metadata.clone();
+    values.insert("primary".to_string(), data.primary.clone());
+    values.insert("secondary".to_string(), data.secondary.clone());
+
+    crate::config::rules::is_visible(
+        config.show_when.as_deref(),
+        config.hide_when.as_deref(),
+        &values,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_dir::TestDir;
+
+    #[test]
+    fn visible_width_ignores_colors_and_hyperlinks() {
+        let widths = WidthConfig::default();
+        let linked = hyperlink("main", "https://github.com/o/r/tree/main");
+        assert_eq!(
+            visible_width(&format!("\x1b[1;34m{}\x1b[0m", linked), &widths),
+            4
+        );
+        assert_eq!(
+            visible_width("\x1b]8;;file:///tmp\x07tmp\x1b]8;;\x07", &widths),
+            3
+        );
+        assert_eq!(visible_width("\x1b[31m项目\x1b[0m", &widths), 4);
+    }
+
+    #[test]
+    fn links_only_built_in_segments_with_safe_schemes() {
+        let mut config = Config::default();
+        config.style.color_mode = crate::config::ColorMode::None;
+        let generator = StatusLineGenerator::new(config.clone());
+
+        let data = |url: &str| SegmentData {
+            primary: "text".to_string(),
+            secondary: String::new(),
+            metadata: [("hyperlink".to_string(), url.to_string())].into(),
+        };
+        let mut directory = config.segments[0].clone();
+        directory.id = SegmentId::Directory;
+        let mut custom = directory.clone();
+        custom.id = SegmentId::Custom;
+
+        assert!(generator
+            .render_segment(&directory, &data("file:///tmp"))
+            .contains("\x1b]8;;file:///tmp"));
+        assert!(!generator
+            .render_segment(&directory, &data("javascript:alert(1)"))
+            .contains("\x1b]8;"));
+        assert!(!generator
+            .render_segment(&custom, &data("https://example.com"))
+            .contains("\x1b]8;"));
+    }
+
+    #[test]
+    fn no_color_emits_no_sgr_even_for_bold_text() {
+        let mut config = Config::default();
+        config.style.color_mode = crate::config::ColorMode::None;
+        let generator = StatusLineGenerator::new(config.clone());
+
+        let mut segment = config.segments[0].clone();
+        segment.styles.text_bold = true;
+        segment.colors.text = Some(AnsiColor::Color16 { c16: 4 });
+        let data = SegmentData {
+            primary: "main".to_string(),
+            secondary: "extra".to_string(),
+            metadata: Default::default(),
+        };
+
+        assert!(!generator.render_segment(&segment, &data).contains('\x1b'));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn disabled_custom_commands_never_run() {
+        let dir = TestDir::new("disabled");
+        let marker = dir.join("ran");
+        let mut custom = Config::default().segments[0].clone();
+        custom.id = SegmentId::Custom;
+        custom.enabled = false;
+        custom.options.insert(
+            "command".to_string(),
+            serde_json::json!(format!("touch {}", marker.display())),
+        );
+        let config = Config {
+            segments: vec![custom],
+            ..Config::default()
+        };
+
+        let collected =
+            collect_all_segments_timed(&config, &crate::config::InputData::for_directory("/tmp"));
+        assert!(collected.is_empty());
+        assert!(!marker.exists());
+    }
+
+    #[test]
+    fn powerline_uses_thin_glyph_between_same_backgrounds() {
+        let mut config = Config::default();
+        config.style.color_mode = crate::config::ColorMode::Ansi16;
+        let generator = StatusLineGenerator::new(config.clone());
+        let rounded = powerline::family_for("\u{e0b4}").unwrap();
+
+        let mut blue = config.segments[0].clone();
+        blue.colors.background = Some(AnsiColor::Color16 { c16: 4 });
+        blue.colors.text = Some(AnsiColor::Color16 { c16: 15 });
+        let mut red = blue.clone();
+        red.colors.background = Some(AnsiColor::Color16 { c16: 1 });
+
+        assert_eq!(
+            generator.powerline_separator(&blue, &blue, rounded),
+            "\x1b[44m\x1b[97m\u{e0b5}\x1b[0m"
+        );
+        assert_eq!(
+            generator.powerline_separator(&blue, &red, rounded),
+            "\x1b[41m\x1b[34m\u{e0b4}\x1b[0m"
+        );
+    }
+    #[test]
+    fn json_lists_enabled_segments_with_schema_version() {
+        let mut config = Config::default();
+        config.style.color_mode = crate::config::ColorMode::None;
+        let generator = StatusLineGenerator::new(config.clone());
+
+        let segment = |index: usize, primary: &str| TimedSegment {
+            config: config.segments[index].clone(),
+            data: SegmentData {
+                primary: primary.to_string(),
+                secondary: String::new(),
+                metadata: [("k".to_string(), "v".to_string())].into(),
+            },
+            elapsed: Duration::from_micros(42),
+        };
+        let mut disabled = segment(1, "hidden");
+        disabled.config.enabled = false;
+
+        let json: serde_json::Value = serde_json::from_str(
+            &generator.generate_json(vec![segment(0, "Opus"), disabled], Duration::ZERO),
+        )
+        .unwrap();
+
+        assert_eq!(json["version"], SCHEMA_VERSION);
+        assert_eq!(json["color_support"], "none");
+        assert_eq!(json["segments"].as_array().unwrap().len(), 1);
+        assert_eq!(json["segments"][0]["primary"], "Opus");
+        assert_eq!(json["segments"][0]["metadata"]["k"], "v");
+        assert_eq!(json["segments"][0]["elapsed_us"], 42);
+    }
 }
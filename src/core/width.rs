@@ -0,0 +1,62 @@
+//! Terminal display width of text, in cells.
+//!
+//! CJK and emoji take two cells. Two groups depend on the terminal and font
+//! and follow [`WidthConfig`]: East Asian ambiguous characters and private-use
+//! glyphs such as Nerd Font icons.
+
+use crate::config::WidthConfig;
+use unicode_width::UnicodeWidthStr;
+
+/// Private-use areas, where Nerd Fonts and Powerline put their glyphs
+fn is_private_use(ch: char) -> bool {
+    matches!(
+        ch as u32,
+        0xE000..=0xF8FF | 0xF0000..=0xFFFFD | 0x100000..=0x10FFFD
+    )
+}
+
+fn run_width(run: &str, widths: &WidthConfig) -> usize {
+    if widths.ambiguous >= 2 {
+        run.width_cjk()
+    } else {
+        run.width()
+    }
+}
+
+/// Display width of text without escape sequences
+pub fn display_width(text: &str, widths: &WidthConfig) -> usize {
+    let mut width = 0;
+    let mut run_start = 0;
+
+    // Measure runs between private-use glyphs as strings so emoji
+    // sequences (variation selectors, ZWJ) are handled as a whole
+    for (i, ch) in text.char_indices() {
+        if is_private_use(ch) {
+            width += run_width(&text[run_start..i], widths);
+            width += widths.private_use as usize;
+            run_start = i + ch.len_utf8();
+        }
+    }
+    width + run_width(&text[run_start..], widths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_cjk_emoji_and_configurable_glyphs() {
+        let widths = WidthConfig::default();
+        assert_eq!(display_width("项目/src", &widths), 8);
+        assert_eq!(display_width("📁 🤖", &widths), 5);
+        assert_eq!(display_width("\u{e0b0}\u{f02a2}", &widths), 2);
+
+        let wide = WidthConfig {
+            ambiguous: 2,
+            private_use: 2,
+        };
+        assert_eq!(display_width("\u{e0b0}\u{f02a2}", &wide), 4);
+        assert_eq!(display_width("·", &wide), 2);
+        assert_eq!(display_width("·", &widths), 1);
+    }
+}
@@ -1,11 +1,28 @@
-use ccometixline::cli::Cli;
-use ccometixline::config::{Config, InputData};
-use ccometixline::core::{collect_all_segments, StatusLineGenerator};
-use std::io::{self, IsTerminal};
+use ccometixline::cli::{Cli, Commands, ModelsCommand, PluginsCommand, RenderArgs};
+use ccometixline::config::{ColorMode, Config, InputData, ModelConfig};
+use ccometixline::core::output::OutputFormat;
+use ccometixline::core::{collect_all_segments, collect_all_segments_timed, StatusLineGenerator};
+use std::io::{self, IsTerminal, Read};
+use std::path::Path;
+use std::time::Instant;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse_args();
 
+    // Handle subcommands
+    if let Some(command) = cli.command {
+        match command {
+            Commands::Models {
+                action: ModelsCommand::Test { model_id },
+            } => test_model_match(&model_id)?,
+            Commands::Plugins {
+                action: PluginsCommand::List,
+            } => list_plugins(),
+            Commands::Render(args) => render(args, cli.theme)?,
+        }
+        return Ok(());
+    }
+
     // Handle configuration commands
     if cli.init {
         Config::init()?;
@@ -13,11 +30,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     if cli.print {
-        let mut config = Config::load().unwrap_or_else(|_| Config::default());
-
-        // Apply theme override if provided
-        if let Some(theme) = cli.theme {
-            config = ccometixline::ui::themes::ThemePresets::get_theme(&theme);
+        // The merged result for this directory, including project files and --theme
+        let current_dir = std::env::current_dir()?;
+        let (config, project_files) = Config::load_layered(&current_dir, cli.theme.as_deref())?;
+        for path in &project_files {
+            println!("# Merged project config: {}", path.display());
         }
 
         config.print()?;
@@ -25,9 +42,20 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     if cli.check {
-        let config = Config::load()?;
+        let current_dir = std::env::current_dir()?;
+        let (config, project_files) = Config::load_layered(&current_dir, None)?;
         config.check()?;
+        if let Some((entry, e)) = ModelConfig::load_strict()?.invalid_entries().first() {
+            return Err(format!(
+                "Invalid {} pattern \"{}\" in models.toml: {}",
+                entry.match_kind, entry.pattern, e
+            )
+            .into());
+        }
         println!("✓ Configuration valid");
+        for path in &project_files {
+            println!("  including {}", path.display());
+        }
         return Ok(());
     }
 
@@ -91,16 +119,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
 
-    // Load configuration
-    let mut config = Config::load().unwrap_or_else(|_| Config::default());
-
-    // Apply theme override if provided
-    if let Some(theme) = cli.theme {
-        config = ccometixline::ui::themes::ThemePresets::get_theme(&theme);
-    }
-
-    // Check if stdin has data
-    if io::stdin().is_terminal() {
+    // Check if stdin has data; other output targets can render without it
+    let interactive = io::stdin().is_terminal();
+    if interactive && cli.output_format == OutputFormat::Ansi {
         // No input data available, show main menu
         #[cfg(feature = "tui")]
         {
@@ -136,17 +157,207 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     // Read Claude Code data from stdin
-    let stdin = io::stdin();
-    let input: InputData = serde_json::from_reader(stdin.lock())?;
+    let mut raw_input = String::new();
+    if !interactive {
+        io::stdin().read_to_string(&mut raw_input)?;
+    }
+    let input: InputData = if raw_input.trim().is_empty() && cli.output_format != OutputFormat::Ansi
+    {
+        // Outside Claude Code (tmux, shell prompts) only the directory is known
+        let current_dir = std::env::current_dir()?;
+        InputData::for_directory(&current_dir.to_string_lossy())
+    } else {
+        serde_json::from_str(&raw_input)?
+    };
+
+    // Load configuration with the project files for the session's directory
+    let project_dir = Path::new(&input.workspace.current_dir);
+    let config = Config::load_layered(project_dir, cli.theme.as_deref())
+        .map(|(config, _)| config)
+        .unwrap_or_else(|_| {
+            // A broken file must not blank the statusline; fall back to the user config
+            let config = Config::load().unwrap_or_else(|_| Config::default());
+
+            // Apply theme override if provided, keeping the terminal's settings
+            match &cli.theme {
+                Some(theme) => config.themed(theme),
+                None => config,
+            }
+        });
+
+    let generator = StatusLineGenerator::new(config.clone()).with_output_format(cli.output_format);
+
+    // Structured output carries the collected data and timings instead of a line
+    if cli.output_format == OutputFormat::Json {
+        let started = Instant::now();
+        let segments = collect_all_segments_timed(&config, &input);
+        println!("{}", generator.generate_json(segments, started.elapsed()));
+        return Ok(());
+    }
 
     // Collect segment data
     let segments_data = collect_all_segments(&config, &input);
 
     // Render statusline
-    let generator = StatusLineGenerator::new(config);
     let statusline = generator.generate(segments_data);
 
     println!("{}", statusline);
 
     Ok(())
 }
+
+/// Print which models.toml entry a model ID resolves to
+fn test_model_match(model_id: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let model_config = ModelConfig::load_strict()?;
+    let matches = model_config.matching_entries(model_id);
+
+    println!("Model ID:      {}", model_id);
+
+    match matches.first() {
+        Some(entry) => {
+            println!(
+                "Matched entry: pattern = \"{}\" (match = {}, priority = {})",
+                entry.pattern, entry.match_kind, entry.priority
+            );
+        }
+        None => println!("Matched entry: none (falling back to Claude Code display name)"),
+    }
+
+    match model_config.get_display_name(model_id) {
+        Some(name) => println!("Display name:  {}", name),
+        None => println!("Display name:  <from Claude Code>"),
+    }
+    println!("Context limit: {}", model_config.get_context_limit(model_id));
+
+    if matches.len() > 1 {
+        println!("\nAlso matched (lower precedence):");
+        for entry in matches.iter().skip(1) {
+            println!(
+                "  pattern = \"{}\" (match = {}, priority = {})",
+                entry.pattern, entry.match_kind, entry.priority
+            );
+        }
+    }
+
+    let invalid = model_config.invalid_entries();
+    if !invalid.is_empty() {
+        println!("\nInvalid patterns (never match):");
+        for (entry, e) in invalid {
+            println!(
+                "  pattern = \"{}\" (match = {}): {}",
+                entry.pattern, entry.match_kind, e
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Render the statusline for each frame, printing it or exporting it as SVG/HTML
+fn render(args: RenderArgs, theme: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+    use ccometixline::core::export::{self, ExportOptions};
+    use ccometixline::core::samples;
+
+    // Project files are looked up from the directory ccline runs in
+    let current_dir = std::env::current_dir()?;
+    let (mut config, _) = Config::load_layered(&current_dir, theme.as_deref())?;
+    // Exports show the theme's own colors, not what this terminal supports
+    let exporting = args.svg.is_some() || args.html.is_some();
+    if exporting && config.style.color_mode == ColorMode::Auto {
+        config.style.color_mode = ColorMode::TrueColor;
+    }
+
+    // Every source yields frames, oldest first; each frame is one statusline
+    let mut frames = if let Some(sample) = args.sample {
+        sample.frames(&config)
+    } else if let Some(path) = &args.transcript {
+        samples::transcript_inputs(path, &std::env::temp_dir(), args.frames, |input| {
+            collect_all_segments(&config, input)
+        })?
+    } else {
+        let raw_input = match &args.input {
+            Some(path) => std::fs::read_to_string(path)?,
+            None if io::stdin().is_terminal() => String::new(),
+            None => {
+                let mut raw_input = String::new();
+                io::stdin().read_to_string(&mut raw_input)?;
+                raw_input
+            }
+        };
+        let inputs = if raw_input.trim().is_empty() {
+            let current_dir = std::env::current_dir()?;
+            vec![InputData::for_directory(&current_dir.to_string_lossy())]
+        } else {
+            samples::parse_inputs(&raw_input)?
+        };
+        inputs
+            .iter()
+            .map(|input| collect_all_segments(&config, input))
+            .collect()
+    };
+
+    if !args.frames && frames.len() > 1 {
+        frames.drain(..frames.len() - 1);
+    }
+    let generator = StatusLineGenerator::new(config.clone());
+    let lines: Vec<String> = frames
+        .into_iter()
+        .map(|segments_data| generator.generate(segments_data))
+        .collect();
+
+    let options = ExportOptions {
+        fonts: args.font,
+        font_size: args.font_size,
+        widths: config.style.width.clone(),
+        ..ExportOptions::default()
+    };
+    if let Some(path) = &args.svg {
+        std::fs::write(path, export::to_svg(&lines, &options))?;
+        println!("Wrote {}", path.display());
+    }
+    if let Some(path) = &args.html {
+        std::fs::write(path, export::to_html(&lines, &options))?;
+        println!("Wrote {}", path.display());
+    }
+    if !exporting {
+        for line in &lines {
+            println!("{}", line);
+        }
+    }
+    Ok(())
+}
+
+/// Print installed segment plugins and whether their handshake succeeded
+fn list_plugins() {
+    use ccometixline::core::segments::plugin;
+
+    let plugins_dir = plugin::plugins_dir();
+    println!("Plugins directory: {}", plugins_dir.display());
+
+    let plugins = plugin::discover_plugins();
+    if plugins.is_empty() {
+        println!("No plugins found (executables named {}*)", plugin::PLUGIN_PREFIX);
+        return;
+    }
+
+    for (path, manifest) in plugins {
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        match manifest {
+            Ok(manifest) => {
+                println!(
+                    "  ✓ {} ({}) v{} - protocol {}{}",
+                    manifest.title(),
+                    manifest.name,
+                    manifest.version.as_deref().unwrap_or("?"),
+                    manifest.protocol,
+                    if manifest.capabilities.is_empty() {
+                        String::new()
+                    } else {
+                        format!(", capabilities: {}", manifest.capabilities.join(", "))
+                    }
+                );
+            }
+            Err(error) => println!("  ✗ {}: {}", file_name, error),
+        }
+    }
+}
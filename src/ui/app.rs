@@ -1,4 +1,5 @@
-use crate::config::{Config, SegmentId, StyleMode};
+use crate::config::{Config, StyleMode};
+use crate::core::segments::SegmentRegistry;
 use crate::ui::components::{
     color_picker::{ColorPickerComponent, NavDirection},
     help::HelpComponent,
@@ -23,9 +24,23 @@ use ratatui::{
     Frame, Terminal,
 };
 use std::io;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::time::Duration;
+
+/// What the name input popup is currently collecting
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NameInputTarget {
+    NewTheme,
+    SegmentName,
+    ShowWhen,
+    HideWhen,
+}
 
 pub struct App {
     config: Config,
+    registry: SegmentRegistry,
+    /// Plugin handshakes run in the background so slow plugins don't delay startup
+    plugin_discovery: Option<Receiver<SegmentRegistry>>,
     selected_segment: usize,
     selected_panel: Panel,
     selected_field: FieldSelection,
@@ -33,6 +48,7 @@ pub struct App {
     color_picker: ColorPickerComponent,
     icon_selector: IconSelectorComponent,
     name_input: NameInputComponent,
+    name_input_target: NameInputTarget,
     preview: PreviewComponent,
     segment_list: SegmentListComponent,
     separator_editor: SeparatorEditorComponent,
@@ -44,8 +60,15 @@ pub struct App {
 
 impl App {
     pub fn new(config: Config) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let _ = sender.send(SegmentRegistry::discover());
+        });
+
         let mut app = Self {
             config: config.clone(),
+            registry: SegmentRegistry::new(),
+            plugin_discovery: Some(receiver),
             selected_segment: 0,
             selected_panel: Panel::SegmentList,
             selected_field: FieldSelection::Enabled,
@@ -53,6 +76,7 @@ impl App {
             color_picker: ColorPickerComponent::new(),
             icon_selector: IconSelectorComponent::new(),
             name_input: NameInputComponent::new(),
+            name_input_target: NameInputTarget::NewTheme,
             preview: PreviewComponent::new(),
             segment_list: SegmentListComponent::new(),
             separator_editor: SeparatorEditorComponent::new(),
@@ -76,9 +100,10 @@ impl App {
 
         // If a theme is specified, reload it to get the latest changes
         if !config.theme.is_empty() && config.theme != "default" {
-            if let Ok(theme_config) =
+            if let Ok(mut theme_config) =
                 crate::ui::themes::ThemePresets::load_theme_from_file(&config.theme)
             {
+                theme_config.trusted_projects = config.trusted_projects;
                 config = theme_config;
             }
         }
@@ -94,8 +119,14 @@ impl App {
 
         // Main loop
         let result = loop {
+            app.poll_plugin_discovery();
             terminal.draw(|f| app.ui(f))?;
 
+            // Redraw once plugin discovery finishes, even without input
+            if app.plugin_discovery.is_some() && !event::poll(Duration::from_millis(100))? {
+                continue;
+            }
+
             if let Event::Key(key) = event::read()? {
                 // Only handle KeyDown events to prevent double triggering on Windows
                 if key.kind != KeyEventKind::Press {
@@ -107,8 +138,21 @@ impl App {
                     match key.code {
                         KeyCode::Esc => app.name_input.close(),
                         KeyCode::Enter => {
-                            if let Some(name) = app.name_input.get_input() {
-                                app.save_as_new_theme(&name);
+                            match app.name_input_target {
+                                NameInputTarget::NewTheme => {
+                                    if let Some(name) = app.name_input.get_input() {
+                                        app.save_as_new_theme(&name);
+                                    }
+                                }
+                                NameInputTarget::SegmentName => {
+                                    let name = app.name_input.get_input();
+                                    app.rename_current_segment(name);
+                                }
+                                target @ (NameInputTarget::ShowWhen
+                                | NameInputTarget::HideWhen) => {
+                                    let rule = app.name_input.get_input();
+                                    app.set_visibility_rule(target, rule);
+                                }
                             }
                             app.name_input.close();
                         }
@@ -122,6 +166,7 @@ impl App {
                         KeyCode::Enter => {
                             let new_separator = app.separator_editor.get_separator();
                             app.config.style.separator = new_separator;
+                            app.config.style.caps = app.separator_editor.get_caps();
                             app.separator_editor.close();
                             app.preview.update_preview(&app.config);
                             app.status_message = Some("Separator updated!".to_string());
@@ -187,6 +232,7 @@ impl App {
                         KeyCode::Char('s') => {
                             if key.modifiers.contains(KeyModifiers::CONTROL) {
                                 // Ctrl+S: Save as new theme with name input
+                                app.name_input_target = NameInputTarget::NewTheme;
                                 app.name_input.open("Save as New Theme", "Enter theme name");
                             } else {
                                 // s: Save config to config.toml
@@ -226,6 +272,9 @@ impl App {
                         KeyCode::Char('p') => app.cycle_theme(),
                         KeyCode::Char('r') => app.reset_to_theme_defaults(),
                         KeyCode::Char('e') | KeyCode::Char('E') => app.open_separator_editor(),
+                        KeyCode::Char('d') | KeyCode::Char('D') => app.duplicate_current_segment(),
+                        KeyCode::Char('n') | KeyCode::Char('N') => app.open_segment_name_input(),
+                        KeyCode::Delete => app.remove_current_segment(),
                         _ => {}
                     }
                 }
@@ -303,6 +352,9 @@ impl App {
                 "[Tab] Switch Panel",
                 "[Enter] Toggle/Edit",
                 "[Shift+↑↓] Reorder",
+                "[D] Duplicate",
+                "[N] Rename",
+                "[Del] Remove",
                 "[1-4] Theme",
                 "[P] Switch Theme",
                 "[R] Reset",
@@ -414,6 +466,7 @@ impl App {
             f,
             content_layout[0],
             &self.config,
+            &self.registry,
             self.selected_segment,
             &self.selected_panel,
         );
@@ -423,6 +476,7 @@ impl App {
             f,
             content_layout[1],
             &self.config,
+            &self.registry,
             self.selected_segment,
             &self.selected_panel,
             &self.selected_field,
@@ -448,21 +502,39 @@ impl App {
             self.name_input.render(f, f.area());
         }
         if self.separator_editor.is_open {
-            self.separator_editor.render(f, f.area());
+            self.separator_editor.render(f, f.area(), &self.config);
+        }
+    }
+
+    /// Switch to the registry with installed plugins once discovery finishes
+    fn poll_plugin_discovery(&mut self) {
+        let Some(receiver) = &self.plugin_discovery else {
+            return;
+        };
+        match receiver.try_recv() {
+            Ok(registry) => {
+                self.registry = registry;
+                self.plugin_discovery = None;
+            }
+            Err(TryRecvError::Disconnected) => self.plugin_discovery = None,
+            Err(TryRecvError::Empty) => {}
         }
     }
 
     fn move_selection(&mut self, delta: i32) {
         match self.selected_panel {
             Panel::SegmentList => {
+                // Installed plugins not yet added are listed after the configured segments
+                let item_count = self.config.segments.len()
+                    + self.registry.available_plugins(&self.config).len();
                 let new_selection = (self.selected_segment as i32 + delta)
                     .max(0)
-                    .min((self.config.segments.len() - 1) as i32)
+                    .min(item_count as i32 - 1)
                     as usize;
                 self.selected_segment = new_selection;
             }
             Panel::Settings => {
-                let field_count = 7; // Enabled, Icon, IconColor, TextColor, TextStyle, BackgroundColor, Options
+                let field_count = 9; // Enabled, Icon, IconColor, TextColor, BackgroundColor, TextStyle, ShowWhen, HideWhen, Options
                 let current_field = match self.selected_field {
                     FieldSelection::Enabled => 0i32,
                     FieldSelection::Icon => 1,
@@ -470,7 +542,9 @@ impl App {
                     FieldSelection::TextColor => 3,
                     FieldSelection::BackgroundColor => 4,
                     FieldSelection::TextStyle => 5,
-                    FieldSelection::Options => 6,
+                    FieldSelection::ShowWhen => 6,
+                    FieldSelection::HideWhen => 7,
+                    FieldSelection::Options => 8,
                 };
                 let new_field = (current_field + delta).clamp(0, field_count - 1) as usize;
                 self.selected_field = match new_field {
@@ -480,7 +554,9 @@ impl App {
                     3 => FieldSelection::TextColor,
                     4 => FieldSelection::BackgroundColor,
                     5 => FieldSelection::TextStyle,
-                    6 => FieldSelection::Options,
+                    6 => FieldSelection::ShowWhen,
+                    7 => FieldSelection::HideWhen,
+                    8 => FieldSelection::Options,
                     _ => FieldSelection::Enabled,
                 };
             }
@@ -490,19 +566,15 @@ impl App {
     fn toggle_current(&mut self) {
         match self.selected_panel {
             Panel::SegmentList => {
+                if self.selected_segment >= self.config.segments.len() {
+                    self.add_selected_plugin();
+                    return;
+                }
+
                 // Toggle segment enabled/disabled in segment list
                 if let Some(segment) = self.config.segments.get_mut(self.selected_segment) {
                     segment.enabled = !segment.enabled;
-                    let segment_name = match segment.id {
-                        SegmentId::Model => "Model",
-                        SegmentId::Directory => "Directory",
-                        SegmentId::Git => "Git",
-                        SegmentId::Usage => "Usage",
-                        SegmentId::Cost => "Cost",
-                        SegmentId::Session => "Session",
-                        SegmentId::OutputStyle => "Output Style",
-                        SegmentId::Update => "Update",
-                    };
+                    let segment_name = self.registry.display_name(segment);
                     let is_enabled = segment.enabled;
                     self.status_message = Some(format!(
                         "{} segment {}",
@@ -519,16 +591,7 @@ impl App {
                         // Toggle enabled state in settings panel too
                         if let Some(segment) = self.config.segments.get_mut(self.selected_segment) {
                             segment.enabled = !segment.enabled;
-                            let segment_name = match segment.id {
-                                SegmentId::Model => "Model",
-                                SegmentId::Directory => "Directory",
-                                SegmentId::Git => "Git",
-                                SegmentId::Usage => "Usage",
-                                SegmentId::Cost => "Cost",
-                                SegmentId::Session => "Session",
-                                SegmentId::OutputStyle => "Output Style",
-                                SegmentId::Update => "Update",
-                            };
+                            let segment_name = self.registry.display_name(segment);
                             let is_enabled = segment.enabled;
                             self.status_message = Some(format!(
                                 "{} segment {}",
@@ -557,6 +620,8 @@ impl App {
                             self.preview.update_preview(&self.config);
                         }
                     }
+                    FieldSelection::ShowWhen => self.open_rule_input(NameInputTarget::ShowWhen),
+                    FieldSelection::HideWhen => self.open_rule_input(NameInputTarget::HideWhen),
                     FieldSelection::Options => {
                         // TODO: Implement options editor
                         self.status_message =
@@ -567,6 +632,26 @@ impl App {
         }
     }
 
+    /// Append the selected entry from the plugin list as a new segment
+    fn add_selected_plugin(&mut self) {
+        let offset = self.selected_segment - self.config.segments.len();
+        let Some(manifest) = self
+            .registry
+            .available_plugins(&self.config)
+            .get(offset)
+            .map(|manifest| (*manifest).clone())
+        else {
+            return;
+        };
+
+        self.config
+            .segments
+            .push(SegmentRegistry::plugin_segment_config(&manifest));
+        self.selected_segment = self.config.segments.len() - 1;
+        self.status_message = Some(format!("{} plugin added", manifest.title()));
+        self.preview.update_preview(&self.config);
+    }
+
     fn switch_panel(&mut self) {
         self.selected_panel = match self.selected_panel {
             Panel::SegmentList => Panel::Settings,
@@ -624,7 +709,8 @@ impl App {
     }
 
     fn switch_to_theme(&mut self, theme_name: &str) {
-        self.config = crate::ui::themes::ThemePresets::get_theme(theme_name);
+        // Color mode, glyph widths and trust describe this machine, not the theme
+        self.config = self.config.themed(theme_name);
         self.selected_segment = 0;
         self.preview.update_preview(&self.config);
         self.status_message = Some(format!("Switched to {} theme", theme_name));
@@ -633,7 +719,7 @@ impl App {
     /// Reset current theme to its default configuration
     fn reset_to_theme_defaults(&mut self) {
         let current_theme = self.config.theme.clone();
-        self.config = crate::ui::themes::ThemePresets::get_theme(&current_theme);
+        self.config = self.config.themed(&current_theme);
         self.selected_segment = 0;
         self.preview.update_preview(&self.config);
         self.status_message = Some(format!("Reset {} theme to defaults", current_theme));
@@ -646,7 +732,10 @@ impl App {
 
     /// Move the currently selected segment up in the list
     fn move_segment_up(&mut self) {
-        if self.selected_panel == Panel::SegmentList && self.selected_segment > 0 {
+        if self.selected_panel == Panel::SegmentList
+            && self.selected_segment > 0
+            && self.selected_segment < self.config.segments.len()
+        {
             let current_idx = self.selected_segment;
             self.config.segments.swap(current_idx, current_idx - 1);
             self.selected_segment -= 1;
@@ -668,6 +757,128 @@ impl App {
         }
     }
 
+    /// Insert a copy of the selected segment below it, under a new unique name
+    fn duplicate_current_segment(&mut self) {
+        if self.selected_panel != Panel::SegmentList {
+            return;
+        }
+        let Some(segment) = self.config.segments.get(self.selected_segment) else {
+            return;
+        };
+
+        let base = segment.key();
+        let name = (2..)
+            .map(|n| format!("{}-{}", base, n))
+            .find(|candidate| !self.config.segments.iter().any(|s| s.key() == *candidate))
+            .unwrap_or(base);
+
+        let mut copy = segment.clone();
+        copy.name = Some(name.clone());
+        self.config.segments.insert(self.selected_segment + 1, copy);
+        self.selected_segment += 1;
+        self.status_message = Some(format!("Duplicated segment as {}", name));
+        self.preview.update_preview(&self.config);
+    }
+
+    /// Remove the selected segment, keeping at least one
+    fn remove_current_segment(&mut self) {
+        if self.selected_panel != Panel::SegmentList
+            || self.selected_segment >= self.config.segments.len()
+        {
+            return;
+        }
+        if self.config.segments.len() == 1 {
+            self.status_message = Some("Cannot remove the last segment".to_string());
+            return;
+        }
+
+        let removed = self.config.segments.remove(self.selected_segment);
+        self.selected_segment = self.selected_segment.min(self.config.segments.len() - 1);
+        self.status_message = Some(format!(
+            "Removed {} segment",
+            self.registry.display_name(&removed)
+        ));
+        self.preview.update_preview(&self.config);
+    }
+
+    fn open_segment_name_input(&mut self) {
+        if self.selected_panel == Panel::SegmentList
+            && self.selected_segment < self.config.segments.len()
+        {
+            self.name_input_target = NameInputTarget::SegmentName;
+            self.name_input
+                .open("Rename Segment", "Instance name (empty to clear)");
+        }
+    }
+
+    /// Set or clear the instance name of the selected segment
+    fn rename_current_segment(&mut self, name: Option<String>) {
+        let index = self.selected_segment;
+        let Some(segment) = self.config.segments.get(index) else {
+            return;
+        };
+
+        let mut renamed = segment.clone();
+        renamed.name = name;
+        let key = renamed.key();
+        let taken = self
+            .config
+            .segments
+            .iter()
+            .enumerate()
+            .any(|(i, s)| i != index && s.key() == key);
+        if taken {
+            self.status_message = Some(format!("Segment key {} is already in use", key));
+            return;
+        }
+
+        self.status_message = Some(format!(
+            "Renamed segment to {}",
+            self.registry.display_name(&renamed)
+        ));
+        self.config.segments[index] = renamed;
+        self.preview.update_preview(&self.config);
+    }
+
+    fn open_rule_input(&mut self, target: NameInputTarget) {
+        let Some(segment) = self.config.segments.get(self.selected_segment) else {
+            return;
+        };
+
+        let (title, current) = match target {
+            NameInputTarget::ShowWhen => ("Show When", &segment.show_when),
+            _ => ("Hide When", &segment.hide_when),
+        };
+        let current = current.clone().unwrap_or_default();
+        self.name_input_target = target;
+        self.name_input
+            .open_text(title, "e.g. cost > 0.5 (empty to clear)", &current);
+    }
+
+    /// Validate and store a visibility rule for the selected segment
+    fn set_visibility_rule(&mut self, target: NameInputTarget, rule: Option<String>) {
+        if let Some(ref source) = rule {
+            if let Err(e) = crate::config::rules::Rule::parse(source) {
+                self.status_message = Some(format!("Invalid rule: {}", e));
+                return;
+            }
+        }
+
+        let Some(segment) = self.config.segments.get_mut(self.selected_segment) else {
+            return;
+        };
+        let field = match target {
+            NameInputTarget::ShowWhen => &mut segment.show_when,
+            _ => &mut segment.hide_when,
+        };
+        self.status_message = Some(match rule {
+            Some(ref source) => format!("Rule set: {}", source),
+            None => "Rule cleared".to_string(),
+        });
+        *field = rule;
+        self.preview.update_preview(&self.config);
+    }
+
     /// Write current config to the current theme file
     fn write_to_current_theme(&mut self) {
         let current_theme = &self.config.theme;
@@ -699,6 +910,7 @@ impl App {
     /// Open separator editor with current separator
     fn open_separator_editor(&mut self) {
         self.status_message = Some("Opening separator editor...".to_string());
-        self.separator_editor.open(&self.config.style.separator);
+        self.separator_editor
+            .open(&self.config.style.separator, &self.config.style.caps);
     }
 }
@@ -5,6 +5,7 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph},
     Frame,
 };
+use unicode_width::UnicodeWidthStr;
 
 #[derive(Default)]
 pub struct HelpComponent;
@@ -42,6 +43,9 @@ impl HelpComponent {
                 ("[Tab]", "Switch Panel"),
                 ("[Enter]", "Toggle/Edit"),
                 ("[Shift+↑↓]", "Reorder"),
+                ("[D]", "Duplicate"),
+                ("[N]", "Rename"),
+                ("[Del]", "Remove"),
                 ("[1-4]", "Theme"),
                 ("[P]", "Switch Theme"),
                 ("[R]", "Reset"),
@@ -63,7 +67,7 @@ impl HelpComponent {
 
         for (i, (key, description)) in help_items.iter().enumerate() {
             // Calculate item display width
-            let item_width = key.chars().count() + description.chars().count() + 1; // +1 for space
+            let item_width = key.width() + description.width() + 1; // +1 for space
 
             // Add separator for non-first items on the same line
             let needs_separator = i > 0 && !current_line_spans.is_empty();
@@ -1,4 +1,5 @@
 use crate::config::StyleMode;
+use crate::core::segments::toolchain::LANGUAGE_ICONS;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Style},
@@ -341,7 +342,7 @@ struct IconInfo {
 }
 
 fn get_plain_icons() -> Vec<IconInfo> {
-    vec![
+    let mut icons = vec![
         IconInfo {
             icon: "🤖",
             name: "Robot (Model)",
@@ -438,11 +439,18 @@ fn get_plain_icons() -> Vec<IconInfo> {
             icon: "◄",
             name: "Left Triangle",
         },
-    ]
+    ];
+
+    // Language icons shared with the Toolchain segment
+    icons.extend(LANGUAGE_ICONS.iter().map(|lang| IconInfo {
+        icon: lang.plain,
+        name: lang.name,
+    }));
+    icons
 }
 
 fn get_nerd_font_icons() -> Vec<IconInfo> {
-    vec![
+    let mut icons = vec![
         IconInfo {
             icon: "\u{e26d}",
             name: "Robot (Model)",
@@ -523,7 +531,14 @@ fn get_nerd_font_icons() -> Vec<IconInfo> {
             icon: "\u{f31b}",
             name: "Github",
         },
-    ]
+    ];
+
+    // Language icons shared with the Toolchain segment
+    icons.extend(LANGUAGE_ICONS.iter().map(|lang| IconInfo {
+        icon: lang.nerd_font,
+        name: lang.name,
+    }));
+    icons
 }
 
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
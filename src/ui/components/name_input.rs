@@ -11,6 +11,8 @@ pub struct NameInputComponent {
     pub input: String,
     pub title: String,
     pub placeholder: String,
+    /// Accept any printable character instead of name characters only
+    pub free_text: bool,
 }
 
 impl Default for NameInputComponent {
@@ -26,6 +28,7 @@ impl NameInputComponent {
             input: String::new(),
             title: "Input Name".to_string(),
             placeholder: "Enter name...".to_string(),
+            free_text: false,
         }
     }
 
@@ -34,6 +37,14 @@ impl NameInputComponent {
         self.input.clear();
         self.title = title.to_string();
         self.placeholder = placeholder.to_string();
+        self.free_text = false;
+    }
+
+    /// Open for free-form text, starting from an existing value
+    pub fn open_text(&mut self, title: &str, placeholder: &str, value: &str) {
+        self.open(title, placeholder);
+        self.input = value.to_string();
+        self.free_text = true;
     }
 
     pub fn close(&mut self) {
@@ -42,7 +53,7 @@ impl NameInputComponent {
     }
 
     pub fn input_char(&mut self, c: char) {
-        if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+        if (self.free_text && !c.is_control()) || c.is_ascii_alphanumeric() || c == '_' || c == '-' {
             self.input.push(c);
         }
     }
@@ -114,7 +125,11 @@ impl NameInputComponent {
                 } else {
                     Style::default().fg(Color::Yellow)
                 })
-                .block(Block::default().borders(Borders::ALL).title("Name")),
+                .block(Block::default().borders(Borders::ALL).title(if self.free_text {
+                    "Value"
+                } else {
+                    "Name"
+                })),
             chunks[0],
         );
 
@@ -1,4 +1,5 @@
-use crate::config::{Config, SegmentId};
+use crate::config::Config;
+use crate::core::samples;
 use crate::core::segments::SegmentData;
 use crate::core::StatusLineGenerator;
 use ratatui::{
@@ -7,7 +8,6 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph},
     Frame,
 };
-use std::collections::HashMap;
 
 pub struct PreviewComponent {
     preview_cache: String,
@@ -87,102 +87,6 @@ impl PreviewComponent {
         &self,
         config: &Config,
     ) -> Vec<(crate::config::SegmentConfig, SegmentData)> {
-        let mut segments_data = Vec::new();
-
-        for segment_config in &config.segments {
-            if !segment_config.enabled {
-                continue;
-            }
-
-            let mock_data = match segment_config.id {
-                SegmentId::Model => SegmentData {
-                    primary: "Sonnet 4".to_string(),
-                    secondary: "".to_string(),
-                    metadata: {
-                        let mut map = HashMap::new();
-                        map.insert("model".to_string(), "claude-4-sonnet-20250512".to_string());
-                        map
-                    },
-                },
-                SegmentId::Directory => SegmentData {
-                    primary: "CCometixLine".to_string(),
-                    secondary: "".to_string(),
-                    metadata: {
-                        let mut map = HashMap::new();
-                        map.insert("current_dir".to_string(), "~/CCometixLine".to_string());
-                        map
-                    },
-                },
-                SegmentId::Git => SegmentData {
-                    primary: "master".to_string(),
-                    secondary: "✓".to_string(),
-                    metadata: {
-                        let mut map = HashMap::new();
-                        map.insert("branch".to_string(), "master".to_string());
-                        map.insert("status".to_string(), "Clean".to_string());
-                        map.insert("ahead".to_string(), "0".to_string());
-                        map.insert("behind".to_string(), "0".to_string());
-                        map
-                    },
-                },
-                SegmentId::Usage => SegmentData {
-                    primary: "78.2%".to_string(),
-                    secondary: "· 156.4k".to_string(),
-                    metadata: {
-                        let mut map = HashMap::new();
-                        map.insert("total_tokens".to_string(), "156400".to_string());
-                        map.insert("percentage".to_string(), "78.2".to_string());
-                        map.insert("session_tokens".to_string(), "48200".to_string());
-                        map
-                    },
-                },
-                SegmentId::Cost => SegmentData {
-                    primary: "$0.02".to_string(),
-                    secondary: "".to_string(),
-                    metadata: {
-                        let mut map = HashMap::new();
-                        map.insert("cost".to_string(), "0.01234".to_string());
-                        map
-                    },
-                },
-                SegmentId::Session => SegmentData {
-                    primary: "3m45s".to_string(),
-                    secondary: "+156 -23".to_string(),
-                    metadata: {
-                        let mut map = HashMap::new();
-                        map.insert("duration_ms".to_string(), "225000".to_string());
-                        map.insert("lines_added".to_string(), "156".to_string());
-                        map.insert("lines_removed".to_string(), "23".to_string());
-                        map
-                    },
-                },
-                SegmentId::OutputStyle => SegmentData {
-                    primary: "default".to_string(),
-                    secondary: "".to_string(),
-                    metadata: {
-                        let mut map = HashMap::new();
-                        map.insert("style_name".to_string(), "default".to_string());
-                        map
-                    },
-                },
-                SegmentId::Update => SegmentData {
-                    primary: format!("v{}", env!("CARGO_PKG_VERSION")),
-                    secondary: "".to_string(),
-                    metadata: {
-                        let mut map = HashMap::new();
-                        map.insert(
-                            "current_version".to_string(),
-                            env!("CARGO_PKG_VERSION").to_string(),
-                        );
-                        map.insert("update_available".to_string(), "false".to_string());
-                        map
-                    },
-                },
-            };
-
-            segments_data.push((segment_config.clone(), mock_data));
-        }
-
-        segments_data
+        samples::preview_segments(config)
     }
 }
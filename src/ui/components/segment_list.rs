@@ -1,4 +1,5 @@
-use crate::config::{Config, SegmentId};
+use crate::config::Config;
+use crate::core::segments::SegmentRegistry;
 use ratatui::{
     layout::Rect,
     style::{Color, Style},
@@ -21,6 +22,8 @@ pub enum FieldSelection {
     TextColor,
     BackgroundColor,
     TextStyle,
+    ShowWhen,
+    HideWhen,
     Options,
 }
 
@@ -37,26 +40,18 @@ impl SegmentListComponent {
         f: &mut Frame,
         area: Rect,
         config: &Config,
+        registry: &SegmentRegistry,
         selected_segment: usize,
         selected_panel: &Panel,
     ) {
-        let items: Vec<ListItem> = config
+        let mut items: Vec<ListItem> = config
             .segments
             .iter()
             .enumerate()
             .map(|(i, segment)| {
                 let is_selected = i == selected_segment && *selected_panel == Panel::SegmentList;
                 let enabled_marker = if segment.enabled { "●" } else { "○" };
-                let segment_name = match segment.id {
-                    SegmentId::Model => "Model",
-                    SegmentId::Directory => "Directory",
-                    SegmentId::Git => "Git",
-                    SegmentId::Usage => "Usage",
-                    SegmentId::Cost => "Cost",
-                    SegmentId::Session => "Session",
-                    SegmentId::OutputStyle => "Output Style",
-                    SegmentId::Update => "Update",
-                };
+                let segment_name = registry.display_name(segment);
 
                 if is_selected {
                     // Selected item with colored cursor
@@ -70,6 +65,32 @@ impl SegmentListComponent {
                 }
             })
             .collect();
+
+        // Installed plugins that can be added with Enter
+        let available = registry.available_plugins(config);
+        if !available.is_empty() {
+            items.push(ListItem::new(Line::from(Span::styled(
+                "  Plugins",
+                Style::default().fg(Color::DarkGray),
+            ))));
+            for (offset, manifest) in available.iter().enumerate() {
+                let is_selected = config.segments.len() + offset == selected_segment
+                    && *selected_panel == Panel::SegmentList;
+                let label = format!("+ {}", manifest.title());
+                if is_selected {
+                    items.push(ListItem::new(Line::from(vec![
+                        Span::styled("▶ ", Style::default().fg(Color::Cyan)),
+                        Span::styled(label, Style::default().fg(Color::DarkGray)),
+                    ])));
+                } else {
+                    items.push(ListItem::new(Line::from(Span::styled(
+                        format!("  {}", label),
+                        Style::default().fg(Color::DarkGray),
+                    ))));
+                }
+            }
+        }
+
         let segments_block = Block::default()
             .borders(Borders::ALL)
             .title("Segments")
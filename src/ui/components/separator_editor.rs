@@ -1,3 +1,6 @@
+use crate::config::{CapsConfig, Config};
+use crate::core::powerline::POWERLINE_FAMILIES;
+use crate::ui::components::preview::PreviewComponent;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Style},
@@ -11,6 +14,8 @@ pub struct SeparatorEditorComponent {
     pub input: String,
     pub presets: Vec<SeparatorPreset>,
     pub selected_preset: Option<usize>,
+    /// Caps applied together with the separator
+    pub caps: CapsConfig,
 }
 
 #[derive(Debug, Clone)]
@@ -18,6 +23,8 @@ pub struct SeparatorPreset {
     pub name: String,
     pub value: String,
     pub description: String,
+    /// Caps set by the preset; `None` leaves the current caps alone
+    pub caps: Option<CapsConfig>,
 }
 
 impl Default for SeparatorEditorComponent {
@@ -33,47 +40,75 @@ impl SeparatorEditorComponent {
             input: String::new(),
             presets: Self::default_presets(),
             selected_preset: None,
+            caps: CapsConfig::default(),
         }
     }
 
     fn default_presets() -> Vec<SeparatorPreset> {
-        vec![
+        let mut presets = vec![
             SeparatorPreset {
                 name: "Pipe".to_string(),
                 value: " | ".to_string(),
                 description: "Classic pipe separator".to_string(),
+                caps: None,
             },
             SeparatorPreset {
                 name: "Thin".to_string(),
                 value: " │ ".to_string(),
                 description: "Thin vertical line".to_string(),
+                caps: None,
             },
-            SeparatorPreset {
-                name: "Arrow".to_string(),
-                value: "\u{e0b0}".to_string(),
-                description: "Powerline arrow (seamless transition)".to_string(),
-            },
+        ];
+
+        // Powerline families (seamless color transitions)
+        presets.extend(POWERLINE_FAMILIES.iter().map(|family| SeparatorPreset {
+            name: family.name.to_string(),
+            value: family.solid.to_string(),
+            description: format!("Powerline {}", family.name.to_lowercase()),
+            caps: None,
+        }));
+
+        // The same families with matching start and end caps
+        presets.extend(POWERLINE_FAMILIES.iter().map(|family| SeparatorPreset {
+            name: format!("{} capped", family.name),
+            value: family.solid.to_string(),
+            description: format!("Powerline {} with caps", family.name.to_lowercase()),
+            caps: Some(CapsConfig {
+                start: family.start_cap.to_string(),
+                end: family.solid.to_string(),
+            }),
+        }));
+
+        presets.extend([
             SeparatorPreset {
                 name: "Space".to_string(),
                 value: "  ".to_string(),
                 description: "Double space".to_string(),
+                caps: None,
             },
             SeparatorPreset {
                 name: "Dot".to_string(),
                 value: " • ".to_string(),
                 description: "Middle dot".to_string(),
+                caps: None,
             },
-        ]
+        ]);
+        presets
     }
 
-    pub fn open(&mut self, current_separator: &str) {
+    pub fn open(&mut self, current_separator: &str, current_caps: &CapsConfig) {
         self.is_open = true;
         self.input = current_separator.to_string();
+        self.caps = current_caps.clone();
         self.selected_preset = None;
 
-        // Check if current separator matches a preset
+        // Check if current separator (and caps, for capped presets) match a preset
         for (i, preset) in self.presets.iter().enumerate() {
-            if preset.value == current_separator {
+            let caps_match = preset
+                .caps
+                .as_ref()
+                .map_or(current_caps.is_empty(), |caps| caps == current_caps);
+            if preset.value == current_separator && caps_match {
                 self.selected_preset = Some(i);
                 break;
             }
@@ -112,6 +147,9 @@ impl SeparatorEditorComponent {
         self.selected_preset = new_selection;
         if let Some(idx) = new_selection {
             self.input = self.presets[idx].value.clone();
+            if let Some(caps) = &self.presets[idx].caps {
+                self.caps = caps.clone();
+            }
         }
     }
 
@@ -119,14 +157,18 @@ impl SeparatorEditorComponent {
         self.input.clone()
     }
 
-    pub fn render(&self, f: &mut Frame, area: Rect) {
+    pub fn get_caps(&self) -> CapsConfig {
+        self.caps.clone()
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect, config: &Config) {
         if !self.is_open {
             return;
         }
 
         // Calculate exact size needed
-        let popup_height = 15;
-        let popup_width = 60;
+        let popup_height = (self.presets.len() as u16 + 14).min(area.height);
+        let popup_width = 72.min(area.width);
         let popup_area = Rect {
             x: (area.width.saturating_sub(popup_width)) / 2,
             y: (area.height.saturating_sub(popup_height)) / 2,
@@ -148,13 +190,22 @@ impl SeparatorEditorComponent {
             .constraints([
                 Constraint::Length(3), // Current input
                 Constraint::Min(5),    // Presets list
+                Constraint::Length(4), // Live preview
                 Constraint::Length(3), // Actions
             ])
             .split(inner);
 
-        // Current input field
+        // Current input field, with the caps that come along
+        let current = if self.caps.is_empty() {
+            format!("> {} <", self.input)
+        } else {
+            format!(
+                "> {} <   caps: {} {}",
+                self.input, self.caps.start, self.caps.end
+            )
+        };
         f.render_widget(
-            Paragraph::new(format!("> {} <", self.input))
+            Paragraph::new(current)
                 .style(Style::default().fg(Color::Yellow))
                 .block(
                     Block::default()
@@ -189,11 +240,19 @@ impl SeparatorEditorComponent {
             chunks[1],
         );
 
+        // Live preview of the statusline with the separator being edited
+        let mut preview_config = config.clone();
+        preview_config.style.separator = self.input.clone();
+        preview_config.style.caps = self.caps.clone();
+        let mut preview = PreviewComponent::new();
+        preview.update_preview_with_width(&preview_config, chunks[2].width);
+        preview.render(f, chunks[2]);
+
         // Actions
         f.render_widget(
             Paragraph::new("[Enter] Confirm  [Esc] Cancel  [Tab] Clear")
                 .block(Block::default().borders(Borders::ALL)),
-            chunks[2],
+            chunks[3],
         );
     }
 }
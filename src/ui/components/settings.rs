@@ -1,5 +1,6 @@
 use super::segment_list::{FieldSelection, Panel};
-use crate::config::{Config, SegmentId, StyleMode};
+use crate::config::{Config, StyleMode};
+use crate::core::segments::SegmentRegistry;
 use ratatui::{
     layout::Rect,
     style::{Color, Style},
@@ -16,26 +17,19 @@ impl SettingsComponent {
         Self
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn render(
         &self,
         f: &mut Frame,
         area: Rect,
         config: &Config,
+        registry: &SegmentRegistry,
         selected_segment: usize,
         selected_panel: &Panel,
         selected_field: &FieldSelection,
     ) {
         if let Some(segment) = config.segments.get(selected_segment) {
-            let segment_name = match segment.id {
-                SegmentId::Model => "Model",
-                SegmentId::Directory => "Directory",
-                SegmentId::Git => "Git",
-                SegmentId::Usage => "Usage",
-                SegmentId::Cost => "Cost",
-                SegmentId::Session => "Session",
-                SegmentId::OutputStyle => "Output Style",
-                SegmentId::Update => "Update",
-            };
+            let segment_name = registry.display_name(segment);
             let current_icon = match config.style.mode {
                 StyleMode::Plain => &segment.icon.plain,
                 StyleMode::NerdFont | StyleMode::Powerline => &segment.icon.nerd_font,
@@ -265,6 +259,20 @@ impl SettingsComponent {
                         }
                     ))],
                 ),
+                create_field_line(
+                    FieldSelection::ShowWhen,
+                    vec![Span::raw(format!(
+                        "├─ Show When: {}",
+                        segment.show_when.as_deref().unwrap_or("always")
+                    ))],
+                ),
+                create_field_line(
+                    FieldSelection::HideWhen,
+                    vec![Span::raw(format!(
+                        "├─ Hide When: {}",
+                        segment.hide_when.as_deref().unwrap_or("never")
+                    ))],
+                ),
                 create_field_line(
                     FieldSelection::Options,
                     vec![Span::raw(format!(
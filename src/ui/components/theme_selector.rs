@@ -4,6 +4,7 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph},
     Frame,
 };
+use unicode_width::UnicodeWidthStr;
 
 #[derive(Default)]
 pub struct ThemeSelectorComponent;
@@ -39,7 +40,7 @@ impl ThemeSelectorComponent {
             let part_with_sep = format!("{}{}", separator, theme_part);
 
             // Check if this part fits in current line
-            let would_fit = current_line.len() + part_with_sep.len() <= content_width as usize;
+            let would_fit = current_line.width() + part_with_sep.width() <= content_width as usize;
 
             if would_fit || first_line {
                 current_line.push_str(&part_with_sep);
@@ -1,6 +1,6 @@
 // Theme presets for TUI configuration
 
-use crate::config::{Config, StyleConfig, StyleMode};
+use crate::config::{CapsConfig, ColorMode, Config, StyleConfig, StyleMode, WidthConfig};
 
 // Import all theme modules
 use super::{
@@ -32,8 +32,20 @@ impl ThemePresets {
         }
     }
 
+    /// Whether a theme name is a plain file name inside the themes directory:
+    /// letters, digits, `-` and `_`
+    pub fn is_valid_theme_name(theme_name: &str) -> bool {
+        !theme_name.is_empty()
+            && theme_name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    }
+
     /// Load theme from file system
     pub fn load_theme_from_file(theme_name: &str) -> Result<Config, Box<dyn std::error::Error>> {
+        if !Self::is_valid_theme_name(theme_name) {
+            return Err(format!("Invalid theme name: {}", theme_name).into());
+        }
         let themes_dir = Self::get_themes_path();
         let theme_path = themes_dir.join(format!("{}.toml", theme_name));
 
@@ -61,6 +73,9 @@ impl ThemePresets {
 
     /// Save current config as a new theme
     pub fn save_theme(theme_name: &str, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+        if !Self::is_valid_theme_name(theme_name) {
+            return Err(format!("Invalid theme name: {}", theme_name).into());
+        }
         let themes_dir = Self::get_themes_path();
         let theme_path = themes_dir.join(format!("{}.toml", theme_name));
 
@@ -70,6 +85,8 @@ impl ThemePresets {
         // Create a copy of config with the correct theme name
         let mut theme_config = config.clone();
         theme_config.theme = theme_name.to_string();
+        // Trust belongs to the user config, not to a shareable theme
+        theme_config.trusted_projects.clear();
 
         let content = toml::to_string_pretty(&theme_config)?;
         std::fs::write(&theme_path, content)?;
@@ -127,6 +144,9 @@ impl ThemePresets {
             style: StyleConfig {
                 mode: StyleMode::NerdFont,
                 separator: " | ".to_string(),
+                color_mode: ColorMode::Auto,
+                width: WidthConfig::default(),
+                caps: CapsConfig::default(),
             },
             segments: vec![
                 theme_cometix::model_segment(),
@@ -138,6 +158,7 @@ impl ThemePresets {
                 theme_cometix::output_style_segment(),
             ],
             theme: "cometix".to_string(),
+            trusted_projects: Vec::new(),
         }
     }
 
@@ -146,6 +167,9 @@ impl ThemePresets {
             style: StyleConfig {
                 mode: StyleMode::Plain,
                 separator: " | ".to_string(),
+                color_mode: ColorMode::Auto,
+                width: WidthConfig::default(),
+                caps: CapsConfig::default(),
             },
             segments: vec![
                 theme_default::model_segment(),
@@ -157,6 +181,7 @@ impl ThemePresets {
                 theme_default::output_style_segment(),
             ],
             theme: "default".to_string(),
+            trusted_projects: Vec::new(),
         }
     }
 
@@ -165,6 +190,9 @@ impl ThemePresets {
             style: StyleConfig {
                 mode: StyleMode::Plain,
                 separator: " │ ".to_string(),
+                color_mode: ColorMode::Auto,
+                width: WidthConfig::default(),
+                caps: CapsConfig::default(),
             },
             segments: vec![
                 theme_minimal::model_segment(),
@@ -176,6 +204,7 @@ impl ThemePresets {
                 theme_minimal::output_style_segment(),
             ],
             theme: "minimal".to_string(),
+            trusted_projects: Vec::new(),
         }
     }
 
@@ -184,6 +213,9 @@ impl ThemePresets {
             style: StyleConfig {
                 mode: StyleMode::NerdFont,
                 separator: " | ".to_string(),
+                color_mode: ColorMode::Auto,
+                width: WidthConfig::default(),
+                caps: CapsConfig::default(),
             },
             segments: vec![
                 theme_gruvbox::model_segment(),
@@ -195,6 +227,7 @@ impl ThemePresets {
                 theme_gruvbox::output_style_segment(),
             ],
             theme: "gruvbox".to_string(),
+            trusted_projects: Vec::new(),
         }
     }
 
@@ -203,6 +236,9 @@ impl ThemePresets {
             style: StyleConfig {
                 mode: StyleMode::NerdFont,
                 separator: "".to_string(),
+                color_mode: ColorMode::Auto,
+                width: WidthConfig::default(),
+                caps: CapsConfig::default(),
             },
             segments: vec![
                 theme_nord::model_segment(),
@@ -214,6 +250,7 @@ impl ThemePresets {
                 theme_nord::output_style_segment(),
             ],
             theme: "nord".to_string(),
+            trusted_projects: Vec::new(),
         }
     }
 
@@ -222,6 +259,9 @@ impl ThemePresets {
             style: StyleConfig {
                 mode: StyleMode::NerdFont,
                 separator: "".to_string(),
+                color_mode: ColorMode::Auto,
+                width: WidthConfig::default(),
+                caps: CapsConfig::default(),
             },
             segments: vec![
                 theme_powerline_dark::model_segment(),
@@ -233,6 +273,7 @@ impl ThemePresets {
                 theme_powerline_dark::output_style_segment(),
             ],
             theme: "powerline-dark".to_string(),
+            trusted_projects: Vec::new(),
         }
     }
 
@@ -241,6 +282,9 @@ impl ThemePresets {
             style: StyleConfig {
                 mode: StyleMode::NerdFont,
                 separator: "".to_string(),
+                color_mode: ColorMode::Auto,
+                width: WidthConfig::default(),
+                caps: CapsConfig::default(),
             },
             segments: vec![
                 theme_powerline_light::model_segment(),
@@ -252,6 +296,7 @@ impl ThemePresets {
                 theme_powerline_light::output_style_segment(),
             ],
             theme: "powerline-light".to_string(),
+            trusted_projects: Vec::new(),
         }
     }
 
@@ -260,6 +305,9 @@ impl ThemePresets {
             style: StyleConfig {
                 mode: StyleMode::NerdFont,
                 separator: "".to_string(),
+                color_mode: ColorMode::Auto,
+                width: WidthConfig::default(),
+                caps: CapsConfig::default(),
             },
             segments: vec![
                 theme_powerline_rose_pine::model_segment(),
@@ -271,6 +319,7 @@ impl ThemePresets {
                 theme_powerline_rose_pine::output_style_segment(),
             ],
             theme: "powerline-rose-pine".to_string(),
+            trusted_projects: Vec::new(),
         }
     }
 
@@ -279,6 +328,9 @@ impl ThemePresets {
             style: StyleConfig {
                 mode: StyleMode::NerdFont,
                 separator: "".to_string(),
+                color_mode: ColorMode::Auto,
+                width: WidthConfig::default(),
+                caps: CapsConfig::default(),
             },
             segments: vec![
                 theme_powerline_tokyo_night::model_segment(),
@@ -290,6 +342,7 @@ impl ThemePresets {
                 theme_powerline_tokyo_night::output_style_segment(),
             ],
             theme: "powerline-tokyo-night".to_string(),
+            trusted_projects: Vec::new(),
         }
     }
 }
@@ -6,6 +6,9 @@ use std::collections::HashMap;
 pub fn model_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::Model,
+        name: None,
+        show_when: None,
+        hide_when: None,
         enabled: true,
         icon: IconConfig {
             plain: "🤖".to_string(),
@@ -30,6 +33,9 @@ pub fn model_segment() -> SegmentConfig {
 pub fn directory_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::Directory,
+        name: None,
+        show_when: None,
+        hide_when: None,
         enabled: true,
         icon: IconConfig {
             plain: "📁".to_string(),
@@ -56,6 +62,9 @@ pub fn directory_segment() -> SegmentConfig {
 pub fn git_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::Git,
+        name: None,
+        show_when: None,
+        hide_when: None,
         enabled: true,
         icon: IconConfig {
             plain: "🌿".to_string(),
@@ -77,6 +86,18 @@ pub fn git_segment() -> SegmentConfig {
             opts.insert("hide_clean_status".to_string(), serde_json::Value::Bool(false));
             opts.insert("branch_max_length".to_string(), serde_json::Value::Number(serde_json::Number::from(20)));
             opts.insert("status_format".to_string(), serde_json::Value::String("symbols".to_string()));
+            // Highlight the segment while an operation is in progress
+            let in_progress = serde_json::json!({ "icon": {"c16": 11}, "text": {"c16": 11} });
+            opts.insert("state_colors".to_string(), serde_json::json!({
+                "rebase": in_progress,
+                "merge": in_progress,
+                "cherry_pick": in_progress,
+                "revert": in_progress,
+                "am": in_progress,
+                "bisect": { "icon": {"c16": 13}, "text": {"c16": 13} },
+            }));
+            // Used once uncommitted work outlives stale_after_minutes
+            opts.insert("stale_colors".to_string(), serde_json::json!({ "icon": {"c16": 9}, "text": {"c16": 9} }));
             opts
         },
     }
@@ -85,6 +106,9 @@ pub fn git_segment() -> SegmentConfig {
 pub fn usage_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::Usage,
+        name: None,
+        show_when: None,
+        hide_when: None,
         enabled: true,
         icon: IconConfig {
             plain: "⚡️".to_string(),
@@ -114,6 +138,9 @@ pub fn usage_segment() -> SegmentConfig {
 pub fn cost_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::Cost,
+        name: None,
+        show_when: None,
+        hide_when: None,
         enabled: false,
         icon: IconConfig {
             plain: "💰".to_string(),
@@ -140,6 +167,9 @@ pub fn cost_segment() -> SegmentConfig {
 pub fn session_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::Session,
+        name: None,
+        show_when: None,
+        hide_when: None,
         enabled: false,
         icon: IconConfig {
             plain: "⏱️".to_string(),
@@ -165,6 +195,9 @@ pub fn session_segment() -> SegmentConfig {
 pub fn output_style_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::OutputStyle,
+        name: None,
+        show_when: None,
+        hide_when: None,
         enabled: false,
         icon: IconConfig {
             plain: "🎯".to_string(),
@@ -6,6 +6,9 @@ use std::collections::HashMap;
 pub fn model_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::Model,
+        name: None,
+        show_when: None,
+        hide_when: None,
         enabled: true,
         icon: IconConfig {
             plain: "🤖".to_string(),
@@ -24,6 +27,9 @@ pub fn model_segment() -> SegmentConfig {
 pub fn directory_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::Directory,
+        name: None,
+        show_when: None,
+        hide_when: None,
         enabled: true,
         icon: IconConfig {
             plain: "📁".to_string(),
@@ -42,6 +48,9 @@ pub fn directory_segment() -> SegmentConfig {
 pub fn git_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::Git,
+        name: None,
+        show_when: None,
+        hide_when: None,
         enabled: true,
         icon: IconConfig {
             plain: "🌿".to_string(),
@@ -56,6 +65,18 @@ pub fn git_segment() -> SegmentConfig {
         options: {
             let mut opts = HashMap::new();
             opts.insert("show_sha".to_string(), serde_json::Value::Bool(false));
+            // Highlight the segment while an operation is in progress
+            let in_progress = serde_json::json!({ "icon": {"c256": 214}, "text": {"c256": 214} });
+            opts.insert("state_colors".to_string(), serde_json::json!({
+                "rebase": in_progress,
+                "merge": in_progress,
+                "cherry_pick": in_progress,
+                "revert": in_progress,
+                "am": in_progress,
+                "bisect": { "icon": {"c256": 175}, "text": {"c256": 175} },
+            }));
+            // Used once uncommitted work outlives stale_after_minutes
+            opts.insert("stale_colors".to_string(), serde_json::json!({ "icon": {"c256": 167}, "text": {"c256": 167} }));
             opts
         },
     }
@@ -64,6 +85,9 @@ pub fn git_segment() -> SegmentConfig {
 pub fn usage_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::Usage,
+        name: None,
+        show_when: None,
+        hide_when: None,
         enabled: true,
         icon: IconConfig {
             plain: "⚡️".to_string(),
@@ -82,6 +106,9 @@ pub fn usage_segment() -> SegmentConfig {
 pub fn cost_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::Cost,
+        name: None,
+        show_when: None,
+        hide_when: None,
         enabled: false,
         icon: IconConfig {
             plain: "💰".to_string(),
@@ -100,6 +127,9 @@ pub fn cost_segment() -> SegmentConfig {
 pub fn session_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::Session,
+        name: None,
+        show_when: None,
+        hide_when: None,
         enabled: false,
         icon: IconConfig {
             plain: "⏱️".to_string(),
@@ -118,6 +148,9 @@ pub fn session_segment() -> SegmentConfig {
 pub fn output_style_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::OutputStyle,
+        name: None,
+        show_when: None,
+        hide_when: None,
         enabled: false,
         icon: IconConfig {
             plain: "🎯".to_string(),
@@ -6,6 +6,9 @@ use std::collections::HashMap;
 pub fn model_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::Model,
+        name: None,
+        show_when: None,
+        hide_when: None,
         enabled: true,
         icon: IconConfig {
             plain: "🤖".to_string(),
@@ -36,6 +39,9 @@ pub fn model_segment() -> SegmentConfig {
 pub fn directory_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::Directory,
+        name: None,
+        show_when: None,
+        hide_when: None,
         enabled: true,
         icon: IconConfig {
             plain: "📁".to_string(),
@@ -66,6 +72,9 @@ pub fn directory_segment() -> SegmentConfig {
 pub fn git_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::Git,
+        name: None,
+        show_when: None,
+        hide_when: None,
         enabled: true,
         icon: IconConfig {
             plain: "🌿".to_string(),
@@ -92,6 +101,18 @@ pub fn git_segment() -> SegmentConfig {
         options: {
             let mut opts = HashMap::new();
             opts.insert("show_sha".to_string(), serde_json::Value::Bool(false));
+            // Highlight the segment while an operation is in progress
+            let in_progress = serde_json::json!({ "icon": {"r": 46, "g": 52, "b": 64}, "text": {"r": 46, "g": 52, "b": 64}, "background": {"r": 235, "g": 203, "b": 139} });
+            opts.insert("state_colors".to_string(), serde_json::json!({
+                "rebase": in_progress,
+                "merge": in_progress,
+                "cherry_pick": in_progress,
+                "revert": in_progress,
+                "am": in_progress,
+                "bisect": { "icon": {"r": 46, "g": 52, "b": 64}, "text": {"r": 46, "g": 52, "b": 64}, "background": {"r": 180, "g": 142, "b": 173} },
+            }));
+            // Used once uncommitted work outlives stale_after_minutes
+            opts.insert("stale_colors".to_string(), serde_json::json!({ "icon": {"r": 46, "g": 52, "b": 64}, "text": {"r": 46, "g": 52, "b": 64}, "background": {"r": 191, "g": 97, "b": 106} }));
             opts
         },
     }
@@ -100,6 +121,9 @@ pub fn git_segment() -> SegmentConfig {
 pub fn usage_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::Usage,
+        name: None,
+        show_when: None,
+        hide_when: None,
         enabled: true,
         icon: IconConfig {
             plain: "⚡️".to_string(),
@@ -130,6 +154,9 @@ pub fn usage_segment() -> SegmentConfig {
 pub fn cost_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::Cost,
+        name: None,
+        show_when: None,
+        hide_when: None,
         enabled: false,
         icon: IconConfig {
             plain: "💰".to_string(),
@@ -160,6 +187,9 @@ pub fn cost_segment() -> SegmentConfig {
 pub fn session_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::Session,
+        name: None,
+        show_when: None,
+        hide_when: None,
         enabled: false,
         icon: IconConfig {
             plain: "⏱️".to_string(),
@@ -190,6 +220,9 @@ pub fn session_segment() -> SegmentConfig {
 pub fn output_style_segment() -> SegmentConfig {
     SegmentConfig {
         id: SegmentId::OutputStyle,
+        name: None,
+        show_when: None,
+        hide_when: None,
         enabled: false,
         icon: IconConfig {
             plain: "🎯".to_string(),
@@ -3,6 +3,9 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "self-update")]
 use chrono::{DateTime, Utc};
 
+/// Release pages of the project on GitHub
+pub const RELEASES_URL: &str = "https://github.com/Haleclipse/CCometixLine/releases";
+
 /// Update status enum
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub enum UpdateStatus {
@@ -73,6 +76,19 @@ impl UpdateState {
         }
     }
 
+    /// Release notes for the version the status text refers to
+    pub fn release_notes_url(&self) -> Option<String> {
+        match &self.status {
+            UpdateStatus::Ready { version, .. } | UpdateStatus::Completed { version, .. } => {
+                Some(format!("{}/tag/v{}", RELEASES_URL, version))
+            }
+            _ => self
+                .latest_version
+                .as_ref()
+                .map(|version| format!("{}/tag/v{}", RELEASES_URL, version)),
+        }
+    }
+
     /// Load update state from config directory and trigger auto-check if needed
     pub fn load() -> Self {
         #[cfg(feature = "self-update")]
@@ -1,3 +1,5 @@
 pub mod claude_code_patcher;
+#[cfg(test)]
+pub(crate) mod test_dir;
 
 pub use claude_code_patcher::{ClaudeCodePatcher, LocationResult};